@@ -0,0 +1,1061 @@
+pub mod analysis;
+pub mod board;
+pub mod domino;
+pub mod events;
+pub mod replay;
+pub mod rules;
+#[cfg(feature = "serde")]
+pub mod save;
+pub mod solver;
+
+use std::collections::{BTreeMap, VecDeque};
+
+use crate::game::board::generator::level::Level;
+use crate::game::board::generator::{LayoutErrors, Seed};
+use crate::game::board::{Board, Position};
+use crate::game::events::{Event, EventKind};
+use crate::game::rules::Rules;
+use domino::Domino;
+
+pub const EXPLOSIVE_VALUE: domino::Value = 0;
+
+/// How many hits after the suggested one [`Game::suggest_move`] looks at.
+pub const DEFAULT_HINT_LOOKAHEAD: usize = 1;
+
+pub type Score = i32;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Game {
+    board: Board,
+    rules: Rules,
+    score: Score,
+    seed: Option<Seed>,
+    history: Vec<Move>,
+    undone: Vec<Move>,
+}
+
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DominoRemoved {
+    pub id: domino::Id,
+    pub exploded: bool,
+    pub hit_by_explosion: bool,
+    pub score_awarded: Score,
+    /// Generation of the chain reaction: 0 for the hit domino, 1 for dominoes blown up by its
+    /// explosion, and so on.
+    pub depth: usize,
+    /// The exploding domino which destroyed this one, if it was not hit.
+    pub parent: Option<domino::Id>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Move {
+    hit: domino::Id,
+    removed: Vec<(domino::Id, Domino)>,
+    score_before: Score,
+}
+
+impl Game {
+    pub fn new(board: Board, rules: Rules) -> Self {
+        Self {
+            board,
+            rules,
+            score: 0,
+            seed: None,
+            history: Vec::new(),
+            undone: Vec::new(),
+        }
+    }
+
+    pub fn new_generated(input: &str) -> Result<Self, LayoutErrors> {
+        Self::new_generated_seeded(input, rand::random())
+    }
+
+    pub fn new_generated_seeded(input: &str, seed: Seed) -> Result<Self, LayoutErrors> {
+        let board = board::generator::generate_from_string_with_seed(input, seed)?;
+        Ok(Self {
+            seed: Some(seed),
+            ..Self::new(board, Rules::classic())
+        })
+    }
+
+    /// Start the level, dealt from its seed or from a random one if it has none.
+    pub fn new_from_level(level: &Level) -> Self {
+        Self::new_from_level_seeded(level, level.info.seed.unwrap_or_else(rand::random))
+    }
+
+    pub fn new_from_level_seeded(level: &Level, seed: Seed) -> Self {
+        Self {
+            seed: Some(seed).filter(|_| level.deals_values()),
+            ..Self::new(level.deal(seed), Rules::classic())
+        }
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn dominoes(&self) -> &BTreeMap<domino::Id, Domino> {
+        self.board.dominoes()
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    pub fn score(&self) -> Score {
+        self.score
+    }
+
+    /// The seed the board was dealt with, if it was generated from a layout.
+    pub fn seed(&self) -> Option<Seed> {
+        self.seed
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.dominoes().is_empty()
+    }
+
+    pub fn moves_made(&self) -> usize {
+        self.history.len()
+    }
+
+    /// The dominoes hit so far, in order, leaving out undone moves.
+    pub fn hits(&self) -> impl Iterator<Item = domino::Id> + '_ {
+        self.history.iter().map(|hit| hit.hit)
+    }
+
+    /// Whether removing the domino makes it explode.
+    pub fn is_explosive(&self, domino: &Domino) -> bool {
+        self.rules.is_explosive(domino.values.head) || self.rules.is_explosive(domino.values.tail)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.history.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    /// Suggest the next hit, with the score it is expected to bring.
+    pub fn suggest_move(&self) -> Option<(domino::Id, Score)> {
+        self.suggest_move_with_lookahead(DEFAULT_HINT_LOOKAHEAD)
+    }
+
+    /// Suggest the next hit, ranking candidates by their immediate gain plus the best gain of
+    /// `lookahead` further hits. Ties go to explosive dominoes, as hitting them keeps more options.
+    pub fn suggest_move_with_lookahead(&self, lookahead: usize) -> Option<(domino::Id, Score)> {
+        self.dominoes()
+            .iter()
+            .map(|(id, domino)| {
+                let mut simulated = self.clone();
+                simulated.hit_domino(*id);
+                let gain = simulated.score - self.score + simulated.best_gain(lookahead);
+                (gain, self.is_explosive(domino), std::cmp::Reverse(*id))
+            })
+            .max()
+            .map(|(gain, _, std::cmp::Reverse(id))| (id, gain))
+    }
+
+    fn best_gain(&self, hits: usize) -> Score {
+        if hits == 0 {
+            return 0;
+        }
+        self.dominoes()
+            .keys()
+            .map(|id| {
+                let mut simulated = self.clone();
+                simulated.hit_domino(*id);
+                simulated.score - self.score + simulated.best_gain(hits - 1)
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn hit_domino(&mut self, id: domino::Id) -> Vec<DominoRemoved> {
+        self.undone.clear();
+        self.perform_hit(id)
+    }
+
+    /// Hit the domino, describing what happened as events ordered by time.
+    pub fn hit_domino_events(&mut self, id: domino::Id) -> Vec<Event> {
+        let outcome = self.hit_domino(id);
+        self.events_of_last_move(&outcome)
+    }
+
+    /// Take back the last hit, returning the dominoes put back on the board.
+    pub fn undo(&mut self) -> Option<Vec<(domino::Id, Domino)>> {
+        let last_move = self.history.pop()?;
+        for (id, domino) in last_move.removed.iter().rev() {
+            self.board.restore_domino(*id, *domino);
+        }
+        self.score = last_move.score_before;
+        let restored = last_move.removed.clone();
+        self.undone.push(last_move);
+        Some(restored)
+    }
+
+    pub fn redo(&mut self) -> Option<Vec<DominoRemoved>> {
+        let undone_move = self.undone.pop()?;
+        Some(self.perform_hit(undone_move.hit))
+    }
+
+    pub fn redo_events(&mut self) -> Option<Vec<Event>> {
+        let outcome = self.redo()?;
+        Some(self.events_of_last_move(&outcome))
+    }
+
+    fn events_of_last_move(&self, outcome: &[DominoRemoved]) -> Vec<Event> {
+        let last_move = self.history.last().expect("No move to describe");
+        let mut events = Vec::new();
+        let mut score = last_move.score_before;
+        let mut step_change = 0;
+        for (index, (removed, (_, domino))) in outcome.iter().zip(&last_move.removed).enumerate() {
+            let time = removed.depth;
+            let kind = match removed.parent {
+                None => EventKind::Hit { id: removed.id },
+                Some(by) => EventKind::Destroyed { id: removed.id, by },
+            };
+            events.push(Event { time, kind });
+            for (origin, tiles) in self.explosions(domino) {
+                let id = removed.id;
+                let kind = EventKind::Exploded { id, origin, tiles };
+                events.push(Event { time, kind });
+            }
+            step_change += removed.score_awarded;
+            let step_ends = outcome.get(index + 1).is_none_or(|next| next.depth != time);
+            if step_ends && step_change != 0 {
+                score += step_change;
+                let kind = EventKind::ScoreChanged {
+                    score,
+                    change: step_change,
+                };
+                events.push(Event { time, kind });
+                step_change = 0;
+            }
+        }
+        if self.is_finished() {
+            let time = events.last().map_or(0, |event| event.time);
+            let kind = EventKind::GameFinished { score: self.score };
+            events.push(Event { time, kind });
+        }
+        events
+    }
+
+    fn perform_hit(&mut self, id: domino::Id) -> Vec<DominoRemoved> {
+        let mut dominoes_removed = Vec::new();
+        let mut exploded_queue = std::collections::VecDeque::new();
+        let mut recorded_move = Move {
+            hit: id,
+            removed: Vec::new(),
+            score_before: self.score,
+        };
+
+        let (hitting_outcome, hit) = self.remove_domino(id, None, 0, 0, &mut exploded_queue);
+        dominoes_removed.push(hitting_outcome);
+        recorded_move.removed.push((id, hit));
+        while let Some((exploded, parent, depth)) = exploded_queue.pop_front() {
+            if self.board.dominoes().contains_key(&exploded) {
+                let removed_before = dominoes_removed.len();
+                let (explosion_outcome, destroyed) = self.remove_domino(
+                    exploded,
+                    Some(parent),
+                    depth,
+                    removed_before,
+                    &mut exploded_queue,
+                );
+                dominoes_removed.push(explosion_outcome);
+                recorded_move.removed.push((exploded, destroyed));
+            }
+        }
+        self.history.push(recorded_move);
+        dominoes_removed
+    }
+
+    fn remove_domino(
+        &mut self,
+        id: domino::Id,
+        parent: Option<domino::Id>,
+        depth: usize,
+        removed_before: usize,
+        exploded_queue: &mut VecDeque<(domino::Id, domino::Id, usize)>,
+    ) -> (DominoRemoved, Domino) {
+        let removed = self.board.remove_domino(id);
+        let exploded = self.handle_possible_explosion(id, removed, depth, exploded_queue);
+        let hit_by_explosion = parent.is_some();
+        let blown_up = hit_by_explosion || exploded;
+        let score_awarded = self
+            .rules
+            .score(removed.values, blown_up, removed_before, depth);
+        self.score += score_awarded;
+        let outcome = DominoRemoved {
+            id,
+            exploded,
+            hit_by_explosion,
+            score_awarded,
+            depth,
+            parent,
+        };
+        (outcome, removed)
+    }
+
+    fn handle_possible_explosion(
+        &self,
+        id: domino::Id,
+        removed: Domino,
+        depth: usize,
+        exploded_queue: &mut VecDeque<(domino::Id, domino::Id, usize)>,
+    ) -> bool {
+        let explosions = self.explosions(&removed);
+        for (_, tiles) in &explosions {
+            for position in tiles {
+                match self.board.tile(*position) {
+                    board::Tile::Empty => {}
+                    board::Tile::Head(hit) | board::Tile::Tail(hit) => {
+                        exploded_queue.push_back((hit, id, depth + 1))
+                    }
+                }
+            }
+        }
+        !explosions.is_empty()
+    }
+
+    /// The positions of the explosive halves of the domino, with the tiles each one blasts.
+    fn explosions(&self, domino: &Domino) -> Vec<(Position, Vec<Position>)> {
+        let possible_explosions = [
+            (domino.values.head, domino.position),
+            (domino.values.tail, domino.tail_position()),
+        ];
+        possible_explosions
+            .into_iter()
+            .filter(|(value, _)| self.rules.is_explosive(*value))
+            .map(|(value, origin)| {
+                let shape = self.rules.explosion_shape(domino.values, value);
+                (origin, shape.blasted_positions(&self.board, origin))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::game::rules::ExplosionShape;
+
+    #[test]
+    fn hitting_normal_domino() {
+        let mut board = Board::new(3, 3);
+        let domino = board.put_domino(Domino {
+            values: (1, 2).into(),
+            position: (1, 1).into(),
+            orientation: domino::Orientation::Horizontal,
+        });
+        let mut game = Game::new(board, Rules::classic());
+        let outcome = game.hit_domino(domino);
+        assert_eq!(
+            outcome,
+            vec![DominoRemoved {
+                id: domino,
+                exploded: false,
+                hit_by_explosion: false,
+                score_awarded: 0,
+                depth: 0,
+                parent: None
+            }]
+        );
+        assert_eq!(game.score(), 0);
+    }
+
+    #[test]
+    fn hitting_hard_domino() {
+        let mut board = Board::new(3, 3);
+        let domino = board.put_domino(Domino {
+            values: (5, 5).into(),
+            position: (1, 1).into(),
+            orientation: domino::Orientation::Vertical,
+        });
+        let mut game = Game::new(board, Rules::classic());
+        let outcome = game.hit_domino(domino);
+        assert_eq!(
+            outcome,
+            vec![DominoRemoved {
+                id: domino,
+                exploded: false,
+                hit_by_explosion: false,
+                score_awarded: -10,
+                depth: 0,
+                parent: None
+            }]
+        );
+        assert_eq!(game.score(), -10);
+    }
+
+    #[test]
+    fn hitting_explosive_domino() {
+        let mut board = Board::new(3, 3);
+        let domino = board.put_domino(Domino {
+            values: (5, 0).into(),
+            position: (1, 1).into(),
+            orientation: domino::Orientation::Vertical,
+        });
+        let mut game = Game::new(board, Rules::classic());
+        let outcome = game.hit_domino(domino);
+        assert_eq!(
+            outcome,
+            vec![DominoRemoved {
+                id: domino,
+                exploded: true,
+                hit_by_explosion: false,
+                score_awarded: 5,
+                depth: 0,
+                parent: None
+            }]
+        );
+        assert_eq!(game.score(), 5);
+    }
+
+    #[test]
+    fn explosion() {
+        let mut board = Board::new(3, 6);
+        let orientation = domino::Orientation::Vertical;
+        let explosive = board.put_domino(Domino {
+            values: (5, 0).into(),
+            position: (1, 2).into(),
+            orientation,
+        });
+
+        let not_destroyed = [
+            Domino {
+                values: (0, 1).into(),
+                position: (0, 0).into(),
+                orientation,
+            },
+            Domino {
+                values: (1, 2).into(),
+                position: (1, 0).into(),
+                orientation,
+            },
+            Domino {
+                values: (2, 3).into(),
+                position: (2, 0).into(),
+                orientation,
+            },
+        ];
+        let expected_remaining: BTreeMap<domino::Id, Domino> = not_destroyed
+            .iter()
+            .map(|domino| (board.put_domino(*domino), *domino))
+            .collect();
+
+        let destroyed = [
+            board.put_domino(Domino {
+                values: (3, 4).into(),
+                position: (0, 2).into(),
+                orientation,
+            }),
+            board.put_domino(Domino {
+                values: (6, 1).into(),
+                position: (2, 2).into(),
+                orientation,
+            }),
+            board.put_domino(Domino {
+                values: (2, 2).into(),
+                position: (0, 4).into(),
+                orientation,
+            }),
+            board.put_domino(Domino {
+                values: (3, 3).into(),
+                position: (1, 4).into(),
+                orientation,
+            }),
+            board.put_domino(Domino {
+                values: (4, 4).into(),
+                position: (2, 4).into(),
+                orientation,
+            }),
+        ];
+
+        let mut game = Game::new(board, Rules::classic());
+
+        let expected_outcome = vec![
+            DominoRemoved {
+                id: explosive,
+                exploded: true,
+                hit_by_explosion: false,
+                score_awarded: 5,
+                depth: 0,
+                parent: None,
+            },
+            DominoRemoved {
+                id: destroyed[1],
+                exploded: false,
+                hit_by_explosion: true,
+                score_awarded: 7,
+                depth: 1,
+                parent: Some(explosive),
+            },
+            DominoRemoved {
+                id: destroyed[4],
+                exploded: false,
+                hit_by_explosion: true,
+                score_awarded: 8,
+                depth: 1,
+                parent: Some(explosive),
+            },
+            DominoRemoved {
+                id: destroyed[3],
+                exploded: false,
+                hit_by_explosion: true,
+                score_awarded: 6,
+                depth: 1,
+                parent: Some(explosive),
+            },
+            DominoRemoved {
+                id: destroyed[2],
+                exploded: false,
+                hit_by_explosion: true,
+                score_awarded: 4,
+                depth: 1,
+                parent: Some(explosive),
+            },
+            DominoRemoved {
+                id: destroyed[0],
+                exploded: false,
+                hit_by_explosion: true,
+                score_awarded: 7,
+                depth: 1,
+                parent: Some(explosive),
+            },
+        ];
+        assert_eq!(game.hit_domino(explosive), expected_outcome);
+        assert_eq!(*game.dominoes(), expected_remaining);
+        assert_eq!(game.score(), 37)
+    }
+
+    #[test]
+    fn chained_explosion() {
+        let mut board = Board::new(4, 7);
+        let orientation = domino::Orientation::Horizontal;
+        let hit = board.put_domino(Domino {
+            values: (0, 0).into(),
+            position: (1, 2).into(),
+            orientation,
+        });
+
+        let not_destroyed = [Domino {
+            values: (3, 3).into(),
+            position: (1, 6).into(),
+            orientation,
+        }];
+        let expected_remaining: BTreeMap<domino::Id, Domino> = not_destroyed
+            .iter()
+            .map(|domino| (board.put_domino(*domino), *domino))
+            .collect();
+
+        let destroyed = [
+            board.put_domino(Domino {
+                values: (3, 2).into(),
+                position: (1, 0).into(),
+                orientation,
+            }),
+            board.put_domino(Domino {
+                values: (3, 0).into(),
+                position: (1, 1).into(),
+                orientation,
+            }),
+            board.put_domino(Domino {
+                values: (4, 0).into(),
+                position: (1, 3).into(),
+                orientation,
+            }),
+            board.put_domino(Domino {
+                values: (5, 0).into(),
+                position: (1, 4).into(),
+                orientation,
+            }),
+            board.put_domino(Domino {
+                values: (1, 3).into(),
+                position: (1, 5).into(),
+                orientation,
+            }),
+        ];
+
+        let mut game = Game::new(board, Rules::classic());
+
+        let expected_outcome = vec![
+            DominoRemoved {
+                id: hit,
+                exploded: true,
+                hit_by_explosion: false,
+                score_awarded: 0,
+                depth: 0,
+                parent: None,
+            },
+            DominoRemoved {
+                id: destroyed[1],
+                exploded: true,
+                hit_by_explosion: true,
+                score_awarded: 3,
+                depth: 1,
+                parent: Some(hit),
+            },
+            DominoRemoved {
+                id: destroyed[2],
+                exploded: true,
+                hit_by_explosion: true,
+                score_awarded: 4,
+                depth: 1,
+                parent: Some(hit),
+            },
+            DominoRemoved {
+                id: destroyed[0],
+                exploded: false,
+                hit_by_explosion: true,
+                score_awarded: 5,
+                depth: 2,
+                parent: Some(destroyed[1]),
+            },
+            DominoRemoved {
+                id: destroyed[3],
+                exploded: true,
+                hit_by_explosion: true,
+                score_awarded: 5,
+                depth: 2,
+                parent: Some(destroyed[2]),
+            },
+            DominoRemoved {
+                id: destroyed[4],
+                exploded: false,
+                hit_by_explosion: true,
+                score_awarded: 4,
+                depth: 3,
+                parent: Some(destroyed[3]),
+            },
+        ];
+        assert_eq!(game.hit_domino(hit), expected_outcome);
+        assert_eq!(*game.dominoes(), expected_remaining);
+        assert_eq!(game.score(), 21);
+    }
+
+    #[test]
+    fn depth_multipliers() {
+        let mut board = Board::new(2, 3);
+        let orientation = domino::Orientation::Horizontal;
+        let dominoes = [(0, 1), (2, 0), (3, 4)]
+            .iter()
+            .zip(0..)
+            .map(|(values, row)| {
+                board.put_domino(Domino {
+                    values: (*values).into(),
+                    position: (0, row).into(),
+                    orientation,
+                })
+            })
+            .collect::<Vec<_>>();
+        let rules = Rules {
+            depth_multipliers: vec![1, 2, 3],
+            ..Rules::classic()
+        };
+        let mut game = Game::new(board, rules);
+        let outcome = game.hit_domino(dominoes[0]);
+        let chain = outcome
+            .iter()
+            .map(|removed| (removed.depth, removed.parent, removed.score_awarded))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            chain,
+            [
+                (0, None, 1),
+                (1, Some(dominoes[0]), 4),
+                (2, Some(dominoes[1]), 21)
+            ]
+        );
+    }
+
+    #[test]
+    fn suggesting_moves() {
+        let mut board = Board::new(4, 2);
+        let orientation = domino::Orientation::Horizontal;
+        let explosive = board.put_domino(Domino {
+            values: (0, 1).into(),
+            position: (0, 0).into(),
+            orientation,
+        });
+        let plain = board.put_domino(Domino {
+            values: (3, 4).into(),
+            position: (2, 0).into(),
+            orientation,
+        });
+        board.put_domino(Domino {
+            values: (2, 2).into(),
+            position: (0, 1).into(),
+            orientation,
+        });
+        let mut game = Game::new(board, Rules::classic());
+
+        assert_eq!(game.suggest_move_with_lookahead(0), Some((explosive, 5)));
+        // Hitting the plain domino first scores the same in two hits; explosive one is preferred.
+        assert_eq!(game.suggest_move_with_lookahead(1), Some((explosive, 5)));
+        game.hit_domino(explosive);
+        assert_eq!(game.suggest_move(), Some((plain, 0)));
+        game.hit_domino(plain);
+        assert_eq!(game.suggest_move(), None);
+    }
+
+    #[test]
+    fn undo_and_redo() {
+        let mut board = Board::new(4, 4);
+        let orientation = domino::Orientation::Horizontal;
+        let explosive = board.put_domino(Domino {
+            values: (0, 2).into(),
+            position: (0, 0).into(),
+            orientation,
+        });
+        let destroyed = board.put_domino(Domino {
+            values: (3, 4).into(),
+            position: (0, 1).into(),
+            orientation,
+        });
+        let double = board.put_domino(Domino {
+            values: (3, 3).into(),
+            position: (0, 3).into(),
+            orientation,
+        });
+        let initial_dominoes = board.dominoes().clone();
+        let mut game = Game::new(board, Rules::classic());
+        assert!(!game.can_undo());
+        assert!(game.undo().is_none());
+
+        let first_hit = game.hit_domino(explosive);
+        game.hit_domino(double);
+        assert_eq!(game.score(), 3);
+        assert!(game.is_finished());
+
+        let restored = game.undo().unwrap();
+        assert_eq!(restored, vec![(double, initial_dominoes[&double])]);
+        assert_eq!(game.score(), 9);
+        let restored = game.undo().unwrap();
+        assert_eq!(
+            restored,
+            vec![
+                (explosive, initial_dominoes[&explosive]),
+                (destroyed, initial_dominoes[&destroyed])
+            ]
+        );
+        assert_eq!(game.score(), 0);
+        assert_eq!(*game.dominoes(), initial_dominoes);
+        assert!(!game.can_undo());
+        assert!(game.can_redo());
+
+        assert_eq!(game.redo(), Some(first_hit));
+        assert_eq!(game.score(), 9);
+        assert!(game.can_redo());
+        game.hit_domino(double);
+        assert!(!game.can_redo());
+        assert!(game.redo().is_none());
+        assert_eq!(game.score(), 3);
+    }
+
+    #[test]
+    fn custom_rules() {
+        let mut board = Board::new(3, 6);
+        let orientation = domino::Orientation::Vertical;
+        let explosive = board.put_domino(Domino {
+            values: (5, 0).into(),
+            position: (1, 2).into(),
+            orientation,
+        });
+        for (values, position) in [((3, 4), (0, 3)), ((2, 5), (2, 4))] {
+            board.put_domino(Domino {
+                values: values.into(),
+                position: position.into(),
+                orientation,
+            });
+        }
+        let double = board.put_domino(Domino {
+            values: (1, 1).into(),
+            position: (0, 0).into(),
+            orientation: domino::Orientation::Horizontal,
+        });
+        let sixes = board.put_domino(Domino {
+            values: (6, 1).into(),
+            position: (2, 0).into(),
+            orientation,
+        });
+        let rules = Rules {
+            explosive_values: vec![0, 6],
+            doubles_penalise: false,
+            explosion_multiplier: 2,
+            combo_percent: 50,
+            ..Rules::classic()
+        };
+        let mut game = Game::new(board, rules);
+
+        let outcome = game.hit_domino(explosive);
+        let scores = outcome.iter().map(|removed| removed.score_awarded);
+        assert_eq!(scores.collect::<Vec<_>>(), [10, 21, 28]);
+        assert_eq!(game.hit_domino(double)[0].score_awarded, 0);
+        assert!(game.is_explosive(&game.dominoes()[&sixes]));
+        assert!(game.hit_domino(sixes)[0].exploded);
+        assert_eq!(game.score(), 59 + 14);
+    }
+
+    #[test]
+    fn events() {
+        let mut board = Board::new(3, 3);
+        let orientation = domino::Orientation::Horizontal;
+        let [hit, exploded, destroyed] =
+            [((0, 1), 0), ((0, 3), 1), ((2, 4), 2)].map(|(values, y)| {
+                board.put_domino(Domino {
+                    values: values.into(),
+                    position: (0, y).into(),
+                    orientation,
+                })
+            });
+        let rules = Rules {
+            explosion_shape: ExplosionShape::Cross,
+            ..Rules::classic()
+        };
+        let mut game = Game::new(board, rules);
+
+        let events = game.hit_domino_events(hit);
+        let tiles = |positions: &[(board::Coord, board::Coord)]| {
+            positions
+                .iter()
+                .map(|position| (*position).into())
+                .collect()
+        };
+        let expected = [
+            (0, EventKind::Hit { id: hit }),
+            (
+                0,
+                EventKind::Exploded {
+                    id: hit,
+                    origin: (0, 0).into(),
+                    tiles: tiles(&[(1, 0), (0, 1)]),
+                },
+            ),
+            (
+                0,
+                EventKind::ScoreChanged {
+                    score: 1,
+                    change: 1,
+                },
+            ),
+            (
+                1,
+                EventKind::Destroyed {
+                    id: exploded,
+                    by: hit,
+                },
+            ),
+            (
+                1,
+                EventKind::Exploded {
+                    id: exploded,
+                    origin: (0, 1).into(),
+                    tiles: tiles(&[(0, 0), (1, 1), (0, 2)]),
+                },
+            ),
+            (
+                1,
+                EventKind::ScoreChanged {
+                    score: 4,
+                    change: 3,
+                },
+            ),
+            (
+                2,
+                EventKind::Destroyed {
+                    id: destroyed,
+                    by: exploded,
+                },
+            ),
+            (
+                2,
+                EventKind::ScoreChanged {
+                    score: 10,
+                    change: 6,
+                },
+            ),
+            (2, EventKind::GameFinished { score: 10 }),
+        ]
+        .map(|(time, kind)| Event { time, kind });
+        assert_eq!(events, expected);
+        assert_eq!(events::chain_length(&events), 2);
+
+        game.undo();
+        assert_eq!(game.redo_events(), Some(events));
+    }
+
+    type Heads = &'static [(board::Coord, board::Coord)];
+
+    /// Fill a 5x6 board with vertical dominoes, hit the one with `values` at `head` and return the
+    /// head positions of the dominoes removed.
+    fn blast(
+        rules: Rules,
+        values: (domino::Value, domino::Value),
+        head: (board::Coord, board::Coord),
+    ) -> std::collections::BTreeSet<(board::Coord, board::Coord)> {
+        let mut board = Board::new(5, 6);
+        let mut hit = None;
+        for y in (0..6).step_by(2) {
+            for x in 0..5 {
+                let values = if (x, y) == head { values } else { (1, 2) };
+                let id = board.put_domino(Domino {
+                    values: values.into(),
+                    position: (x, y).into(),
+                    orientation: domino::Orientation::Vertical,
+                });
+                if (x, y) == head {
+                    hit = Some(id);
+                }
+            }
+        }
+        let mut game = Game::new(board, rules);
+        let dominoes = game.dominoes().clone();
+        let outcome = game.hit_domino(hit.unwrap());
+        let positions = outcome.iter().map(|removed| dominoes[&removed.id].position);
+        positions.map(|position| (position.x, position.y)).collect()
+    }
+
+    fn shaped(shape: ExplosionShape) -> Rules {
+        Rules {
+            explosion_shape: shape,
+            ..Rules::classic()
+        }
+    }
+
+    #[test]
+    fn explosion_shapes() {
+        let cases: [(ExplosionShape, Heads); 4] = [
+            (ExplosionShape::Cross, &[(2, 0), (1, 2), (3, 2)]),
+            (
+                ExplosionShape::Ring,
+                &[(1, 0), (2, 0), (3, 0), (1, 2), (3, 2)],
+            ),
+            (
+                ExplosionShape::Diamond(2),
+                &[
+                    (1, 0),
+                    (2, 0),
+                    (3, 0),
+                    (0, 2),
+                    (1, 2),
+                    (3, 2),
+                    (4, 2),
+                    (2, 4),
+                ],
+            ),
+            (
+                ExplosionShape::Line,
+                &[(2, 0), (0, 2), (1, 2), (3, 2), (4, 2), (2, 4)],
+            ),
+        ];
+        for (shape, blasted) in cases {
+            let mut expected: std::collections::BTreeSet<_> = blasted.iter().copied().collect();
+            expected.insert((2, 2));
+            assert_eq!(
+                blast(shaped(shape), (0, 1), (2, 2)),
+                expected,
+                "{:?}",
+                shape
+            );
+        }
+    }
+
+    #[test]
+    fn explosion_shapes_are_clipped_at_board_edges() {
+        let cases: [(ExplosionShape, Heads, Heads); 4] = [
+            (ExplosionShape::Cross, &[(1, 0)], &[(3, 4)]),
+            (ExplosionShape::Ring, &[(1, 0)], &[(3, 4)]),
+            (
+                ExplosionShape::Diamond(2),
+                &[(1, 0), (2, 0), (0, 2)],
+                &[(4, 2), (2, 4), (3, 4)],
+            ),
+            (
+                ExplosionShape::Line,
+                &[(1, 0), (2, 0), (3, 0), (4, 0), (0, 2), (0, 4)],
+                &[(4, 0), (4, 2), (0, 4), (1, 4), (2, 4), (3, 4)],
+            ),
+        ];
+        for (shape, from_top_left, from_bottom_right) in cases {
+            let mut expected: std::collections::BTreeSet<_> =
+                from_top_left.iter().copied().collect();
+            expected.insert((0, 0));
+            assert_eq!(
+                blast(shaped(shape), (0, 1), (0, 0)),
+                expected,
+                "{:?}",
+                shape
+            );
+
+            let mut expected: std::collections::BTreeSet<_> =
+                from_bottom_right.iter().copied().collect();
+            expected.insert((4, 4));
+            assert_eq!(
+                blast(shaped(shape), (1, 0), (4, 4)),
+                expected,
+                "{:?}",
+                shape
+            );
+        }
+    }
+
+    #[test]
+    fn explosion_shapes_by_value() {
+        let rules = Rules {
+            explosive_values: vec![0, 6],
+            explosion_shape: ExplosionShape::Cross,
+            value_explosion_shapes: [(0, ExplosionShape::Ring)].into_iter().collect(),
+            double_explosion_shape: Some(ExplosionShape::Line),
+            ..Rules::classic()
+        };
+        assert_eq!(
+            blast(rules.clone(), (6, 1), (2, 2)),
+            blast(shaped(ExplosionShape::Cross), (0, 1), (2, 2))
+        );
+        assert_eq!(
+            blast(rules.clone(), (0, 1), (2, 2)),
+            blast(shaped(ExplosionShape::Ring), (0, 1), (2, 2))
+        );
+        assert_eq!(
+            blast(rules, (0, 0), (2, 2)),
+            blast(shaped(ExplosionShape::Line), (0, 1), (2, 2))
+        );
+    }
+}
+
+#[test]
+fn on_edge_explosions_do_not_panic() {
+    let mut board = Board::new(4, 4);
+    let hit = board.put_domino(Domino {
+        values: (0, 0).into(),
+        position: (0, 1).into(),
+        orientation: domino::Orientation::Vertical,
+    });
+    board.put_domino(Domino {
+        values: (0, 0).into(),
+        position: (1, 0).into(),
+        orientation: domino::Orientation::Horizontal,
+    });
+    board.put_domino(Domino {
+        values: (0, 0).into(),
+        position: (3, 1).into(),
+        orientation: domino::Orientation::Vertical,
+    });
+    board.put_domino(Domino {
+        values: (0, 0).into(),
+        position: (1, 3).into(),
+        orientation: domino::Orientation::Horizontal,
+    });
+
+    let mut game = Game::new(board, Rules::classic());
+
+    let outcome = game.hit_domino(hit);
+
+    assert_eq!(outcome.len(), 4);
+}
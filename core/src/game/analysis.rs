@@ -0,0 +1,168 @@
+//! Rating how hard a board is: how much playing it well beats playing it naively, and how big it
+//! is.
+//!
+//! Under the classic rules every explosive domino left on the board eventually blows up whatever
+//! the order of hits, so greedy play often finds the best score and what sets boards apart is
+//! how much careless play loses. Rules rewarding long chains make the order matter too.
+
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::game::board::generator::level::Difficulty;
+use crate::game::board::generator::Seed;
+use crate::game::events::chain_length;
+use crate::game::{solver, Game, Score};
+
+/// Random games played to estimate the score of careless play.
+const RANDOM_PLAYOUTS: usize = 32;
+const PLAYOUT_SEED: Seed = 0;
+/// Dominoes adding a point to the rating.
+const DOMINOES_PER_POINT: f64 = 40.0;
+/// Rating from which a board gets each grade.
+const GRADE_THRESHOLDS: [(f64, Difficulty); 3] = [
+    (1.2, Difficulty::Expert),
+    (0.9, Difficulty::Hard),
+    (0.7, Difficulty::Medium),
+];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Analysis {
+    pub dominoes: usize,
+    /// The best final score, as found by [`solver::solve`].
+    pub optimal_score: Score,
+    /// The final score of always taking the hit which scores most right away.
+    pub greedy_score: Score,
+    /// The average final score of hitting dominoes at random.
+    pub random_score: f64,
+    /// Domino halves which make their domino explode.
+    pub explosive_tiles: usize,
+    pub doubles: usize,
+    /// Steps of the chain reaction started by hitting each explosive domino, on average.
+    pub average_chain: f64,
+}
+
+impl Analysis {
+    /// The share of the optimal score missed by random play, twice the share missed by greedy
+    /// play, and a point for every [`DOMINOES_PER_POINT`] dominoes.
+    pub fn rating(&self) -> f64 {
+        let missed_share = |score: f64| {
+            (self.optimal_score as f64 - score) / self.optimal_score.abs().max(1) as f64
+        };
+        missed_share(self.random_score)
+            + 2.0 * missed_share(self.greedy_score as f64)
+            + self.dominoes as f64 / DOMINOES_PER_POINT
+    }
+
+    pub fn difficulty(&self) -> Difficulty {
+        let rating = self.rating();
+        GRADE_THRESHOLDS
+            .iter()
+            .find(|(threshold, _)| rating >= *threshold)
+            .map_or(Difficulty::Easy, |(_, difficulty)| *difficulty)
+    }
+}
+
+/// Analyse the game from its current position, with its rules.
+pub fn analyse(game: &Game) -> Analysis {
+    let dominoes = game.dominoes();
+    let explosive_tiles = dominoes
+        .values()
+        .flat_map(|domino| [domino.values.head, domino.values.tail])
+        .filter(|value| game.rules().is_explosive(*value))
+        .count();
+    let doubles = dominoes
+        .values()
+        .filter(|domino| domino.values.head == domino.values.tail)
+        .count();
+    let chains: Vec<usize> = dominoes
+        .iter()
+        .filter(|(_, domino)| game.is_explosive(domino))
+        .map(|(id, _)| chain_length(&game.clone().hit_domino_events(*id)))
+        .collect();
+    let average_chain = match chains.len() {
+        0 => 0.0,
+        count => chains.iter().sum::<usize>() as f64 / count as f64,
+    };
+    Analysis {
+        dominoes: dominoes.len(),
+        optimal_score: solver::solve(game).score,
+        greedy_score: greedy_score(game),
+        random_score: random_score(game),
+        explosive_tiles,
+        doubles,
+        average_chain,
+    }
+}
+
+fn greedy_score(game: &Game) -> Score {
+    let mut game = game.clone();
+    while let Some((id, _)) = game.suggest_move_with_lookahead(0) {
+        game.hit_domino(id);
+    }
+    game.score()
+}
+
+fn random_score(game: &Game) -> f64 {
+    let mut rng = ChaCha8Rng::seed_from_u64(PLAYOUT_SEED);
+    let mut total = 0;
+    for _ in 0..RANDOM_PLAYOUTS {
+        let mut game = game.clone();
+        while !game.is_finished() {
+            let index = rng.next_u64() % game.dominoes().len() as u64;
+            let id = *game.dominoes().keys().nth(index as usize).unwrap();
+            game.hit_domino(id);
+        }
+        total += game.score();
+    }
+    total as f64 / RANDOM_PLAYOUTS as f64
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::game::board::generator::level::Level;
+
+    fn analyse_level(level: &str) -> Analysis {
+        analyse(&Game::new_from_level(&Level::parse(level).unwrap()))
+    }
+
+    #[test]
+    fn metrics() {
+        // The blank blows up the 3-3 below it, unless the 3-3 is hit first and costs 6.
+        let analysis = analyse_level("[layout]\n--|\n--|\n[values]\n011\n332");
+        assert_eq!(analysis.dominoes, 3);
+        assert_eq!(analysis.explosive_tiles, 1);
+        assert_eq!(analysis.doubles, 1);
+        assert_eq!(analysis.average_chain, 1.0);
+        assert_eq!(analysis.optimal_score, 7);
+        assert_eq!(analysis.greedy_score, 7);
+        assert!(analysis.random_score < 7.0 && analysis.random_score > -6.0);
+
+        let analysis = analyse_level("[layout]\n----\n[values]\n1234");
+        assert_eq!(analysis.explosive_tiles, 0);
+        assert_eq!(analysis.average_chain, 0.0);
+        assert_eq!(analysis.random_score, 0.0);
+        assert_eq!(analysis.difficulty(), Difficulty::Easy);
+    }
+
+    #[test]
+    fn grades() {
+        let graded = |dominoes, greedy_score, random_score| {
+            Analysis {
+                dominoes,
+                optimal_score: 100,
+                greedy_score,
+                random_score,
+                explosive_tiles: 0,
+                doubles: 0,
+                average_chain: 0.0,
+            }
+            .difficulty()
+        };
+        assert_eq!(graded(8, 100, 60.0), Difficulty::Easy);
+        assert_eq!(graded(20, 100, 70.0), Difficulty::Medium);
+        assert_eq!(graded(20, 90, 70.0), Difficulty::Hard);
+        assert_eq!(graded(36, 100, 40.0), Difficulty::Expert);
+        assert_eq!(graded(0, 100, 100.0), Difficulty::Easy);
+    }
+}
@@ -6,6 +6,7 @@ use crate::game::domino::Domino;
 pub type Coord = u32;
 
 #[derive(Copy, Clone, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Position {
     pub x: Coord,
     pub y: Coord,
@@ -79,13 +80,32 @@ impl Iterator for DirectionIter {
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Tile {
     Empty,
     Head(domino::Id),
     Tail(domino::Id),
 }
 
-#[derive(Clone, Debug)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PlacementError {
+    OutOfBounds(Position),
+    Occupied(Position),
+}
+
+impl std::fmt::Display for PlacementError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OutOfBounds(position) => write!(f, "tile {} out of bounds", position),
+            Self::Occupied(position) => write!(f, "tile {} already occupied", position),
+        }
+    }
+}
+
+impl std::error::Error for PlacementError {}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Board {
     tiles: Vec<Tile>,
     width: Coord,
@@ -97,7 +117,7 @@ impl Board {
     pub fn new(width: Coord, height: Coord) -> Self {
         let tiles_len = (width * height) as usize;
         Board {
-            tiles: std::iter::repeat(Tile::Empty).take(tiles_len).collect(),
+            tiles: std::iter::repeat_n(Tile::Empty, tiles_len).collect(),
             width,
             dominoes: std::collections::BTreeMap::new(),
             next_domino_id: domino::Id::default(),
@@ -112,6 +132,10 @@ impl Board {
         self.tiles.len() as Coord / self.width
     }
 
+    pub fn contains(&self, position: Position) -> bool {
+        self.checked_index_of_tile(position).is_ok()
+    }
+
     pub fn tile(&self, position: Position) -> Tile {
         self.tiles[self.index_of_tile(position)]
     }
@@ -154,23 +178,24 @@ impl Board {
     }
 
     pub fn put_domino(&mut self, domino: Domino) -> domino::Id {
+        self.try_put_domino(domino)
+            .unwrap_or_else(|error| panic!("Cannot put domino {}: {}", domino, error))
+    }
+
+    pub fn try_put_domino(&mut self, domino: Domino) -> Result<domino::Id, PlacementError> {
         let new_id = self.next_domino_id;
-        for (tile_pos, new_tile) in &[
-            (domino.position, Tile::Head(new_id)),
-            (domino.tail_position(), Tile::Tail(new_id)),
-        ] {
-            let tile = self.tile_mut(*tile_pos);
-            if *tile != Tile::Empty {
-                panic!(
-                    "Put domino {} at already occupied tile {}",
-                    domino, tile_pos
-                );
-            }
-            *tile = *new_tile;
-        }
-        self.dominoes.insert(new_id, domino);
+        self.place_domino(new_id, domino)?;
         self.next_domino_id += 1;
-        new_id
+        Ok(new_id)
+    }
+
+    /// Put back a domino previously taken by [`Board::remove_domino`], keeping its original id.
+    pub fn restore_domino(&mut self, id: domino::Id, domino: Domino) {
+        if id >= self.next_domino_id || self.dominoes.contains_key(&id) {
+            panic!("Restoring domino with unexpected id {}", id);
+        }
+        self.place_domino(id, domino)
+            .unwrap_or_else(|error| panic!("Cannot restore domino {}: {}", domino, error));
     }
 
     pub fn remove_domino(&mut self, domino: domino::Id) -> Domino {
@@ -184,12 +209,35 @@ impl Board {
         removed
     }
 
-    fn index_of_tile(&self, position: Position) -> usize {
+    fn place_domino(&mut self, id: domino::Id, domino: Domino) -> Result<(), PlacementError> {
+        let head_index = self.checked_index_of_tile(domino.position)?;
+        let tail_index = self.checked_index_of_tile(domino.tail_position())?;
+        for (index, position) in &[
+            (head_index, domino.position),
+            (tail_index, domino.tail_position()),
+        ] {
+            if self.tiles[*index] != Tile::Empty {
+                return Err(PlacementError::Occupied(*position));
+            }
+        }
+        self.tiles[head_index] = Tile::Head(id);
+        self.tiles[tail_index] = Tile::Tail(id);
+        self.dominoes.insert(id, domino);
+        Ok(())
+    }
+
+    fn checked_index_of_tile(&self, position: Position) -> Result<usize, PlacementError> {
         let index = (position.y * self.width + position.x) as usize;
         if position.x >= self.width || index >= self.tiles.len() {
-            panic!("Tile {} out of bounds", position);
+            Err(PlacementError::OutOfBounds(position))
+        } else {
+            Ok(index)
         }
-        index
+    }
+
+    fn index_of_tile(&self, position: Position) -> usize {
+        self.checked_index_of_tile(position)
+            .unwrap_or_else(|_| panic!("Tile {} out of bounds", position))
     }
 
     fn tile_mut(&mut self, position: Position) -> &mut Tile {
@@ -311,6 +359,36 @@ mod test {
         });
     }
 
+    #[test]
+    fn trying_to_put_dominoes() {
+        let mut board = Board::new(3, 3);
+        let domino = Domino {
+            values: (1, 2).into(),
+            position: (1, 1).into(),
+            orientation: domino::Orientation::Horizontal,
+        };
+        let id = board.try_put_domino(domino).unwrap();
+        assert_eq!(
+            board.try_put_domino(Domino {
+                position: (2, 0).into(),
+                orientation: domino::Orientation::Vertical,
+                ..domino
+            }),
+            Err(PlacementError::Occupied((2, 1).into()))
+        );
+        assert_eq!(
+            board.try_put_domino(Domino {
+                position: (0, 2).into(),
+                orientation: domino::Orientation::Vertical,
+                ..domino
+            }),
+            Err(PlacementError::OutOfBounds((0, 3).into()))
+        );
+        assert_eq!(board.dominoes().keys().collect_vec(), vec![&id]);
+        assert_empty(&board, (2, 0));
+        assert_empty(&board, (0, 2));
+    }
+
     #[test]
     fn removing_dominoes() {
         let mut board = Board::new(3, 3);
@@ -337,4 +415,27 @@ mod test {
             vec![(&second_id, &second_domino)]
         );
     }
+
+    #[test]
+    fn restoring_dominoes() {
+        let mut board = Board::new(3, 3);
+        let domino = Domino {
+            values: (1, 2).into(),
+            position: (0, 0).into(),
+            orientation: domino::Orientation::Vertical,
+        };
+        let id = board.put_domino(domino);
+        let removed = board.remove_domino(id);
+        board.restore_domino(id, removed);
+        assert_eq!(board.tile((0, 0).into()), Tile::Head(id));
+        assert_eq!(board.tile((0, 1).into()), Tile::Tail(id));
+        assert_eq!(board.dominoes().iter().collect_vec(), vec![(&id, &domino)]);
+        assert_ne!(
+            board.put_domino(Domino {
+                position: (1, 0).into(),
+                ..domino
+            }),
+            id
+        );
+    }
 }
@@ -0,0 +1,844 @@
+//! Building boards from layout strings.
+//!
+//! The layout only says where the dominoes are; their values are dealt by shuffling a [`DominoSet`]
+//! with a ChaCha8 generator seeded through [`rand::SeedableRng::seed_from_u64`]. Both are
+//! documented by their crates as reproducible across platforms and releases, and the shuffle itself
+//! is implemented here rather than borrowed from `rand`, so a layout and a [`Seed`] always deal the
+//! same board.
+//!
+//! Values may also be fixed by a second grid aligned with the layout, holding a hexadecimal digit
+//! for each half of the dominoes whose values are fixed. Other dominoes are dealt from what remains
+//! of the set.
+//!
+//! The layouts themselves can be drawn at random by [`procedural`], and deals meeting constraints
+//! are found by [`constrained`].
+
+use itertools::Itertools;
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::collections::{BTreeSet, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::game::board;
+use crate::game::board::{Board, Tile};
+use crate::game::domino;
+use crate::game::domino::Domino;
+
+pub mod constrained;
+pub mod level;
+pub mod procedural;
+
+pub type Seed = u64;
+
+/// The dominoes values are dealt from: one domino for each pair of values up to the highest.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct DominoSet {
+    max_value: domino::Value,
+}
+
+impl DominoSet {
+    pub const DOUBLE_SIX: Self = Self { max_value: 6 };
+    pub const DOUBLE_NINE: Self = Self { max_value: 9 };
+    pub const DOUBLE_TWELVE: Self = Self { max_value: 12 };
+    pub const DOUBLE_FIFTEEN: Self = Self { max_value: 15 };
+
+    /// Values are written as single hexadecimal digits, so no set goes above double-fifteen.
+    pub const MAX_SUPPORTED_VALUE: domino::Value = 15;
+
+    pub fn new(max_value: domino::Value) -> Option<Self> {
+        Some(Self { max_value }).filter(|_| max_value <= Self::MAX_SUPPORTED_VALUE)
+    }
+
+    pub fn max_value(self) -> domino::Value {
+        self.max_value
+    }
+
+    pub fn domino_count(self) -> usize {
+        let values = self.max_value as usize + 1;
+        values * (values + 1) / 2
+    }
+
+    pub fn contains(self, values: domino::Values) -> bool {
+        values.head <= self.max_value && values.tail <= self.max_value
+    }
+
+    fn values(self) -> DominoValuesGenerator {
+        DominoValuesGenerator::new(self)
+    }
+}
+
+impl Default for DominoSet {
+    fn default() -> Self {
+        Self::DOUBLE_SIX
+    }
+}
+
+const SET_NAMES: [(DominoSet, &str); 4] = [
+    (DominoSet::DOUBLE_SIX, "double-six"),
+    (DominoSet::DOUBLE_NINE, "double-nine"),
+    (DominoSet::DOUBLE_TWELVE, "double-twelve"),
+    (DominoSet::DOUBLE_FIFTEEN, "double-fifteen"),
+];
+
+impl fmt::Display for DominoSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match SET_NAMES.iter().find(|(set, _)| set == self) {
+            Some((_, name)) => write!(f, "{}", name),
+            None => write!(f, "double-{}", self.max_value),
+        }
+    }
+}
+
+impl FromStr for DominoSet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.to_lowercase();
+        if let Some((set, _)) = SET_NAMES.iter().find(|(_, name)| *name == s) {
+            return Ok(*set);
+        }
+        s.strip_prefix("double-")
+            .and_then(|max_value| max_value.parse().ok())
+            .and_then(Self::new)
+            .ok_or_else(|| format!("unknown domino set \"{}\"", s))
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum LayoutErrorKind {
+    EmptyInput,
+    InconsistentOrientation,
+    DanglingHalf,
+    TooManyDominoes { available: usize },
+    MissingValue,
+    ValueOutOfRange { max: domino::Value },
+    DuplicateDomino,
+}
+
+impl fmt::Display for LayoutErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyInput => write!(f, "the layout contains no dominoes"),
+            Self::InconsistentOrientation => {
+                write!(f, "domino half is joined with a half of other orientation")
+            }
+            Self::DanglingHalf => write!(f, "domino half has no other half"),
+            Self::TooManyDominoes { available } => write!(
+                f,
+                "the layout has more dominoes than the {} in the set",
+                available
+            ),
+            Self::MissingValue => write!(f, "the other half of this domino has a value"),
+            Self::ValueOutOfRange { max } => write!(f, "values must be between 0 and {}", max),
+            Self::DuplicateDomino => write!(f, "the set has only one domino with these values"),
+        }
+    }
+}
+
+/// Problem found in a layout string. Lines and columns are counted from 1.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct LayoutError {
+    pub line: usize,
+    pub column: usize,
+    pub character: Option<char>,
+    pub kind: LayoutErrorKind,
+}
+
+impl LayoutError {
+    fn at(position: board::Position, character: Option<char>, kind: LayoutErrorKind) -> Self {
+        Self {
+            line: position.y as usize + 1,
+            column: position.x as usize + 1,
+            character,
+            kind,
+        }
+    }
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)?;
+        if let Some(character) = self.character {
+            write!(f, " ('{}')", character)?;
+        }
+        write!(f, ": {}", self.kind)
+    }
+}
+
+impl std::error::Error for LayoutError {}
+
+/// All problems found in a layout, ordered by line and column.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LayoutErrors(pub Vec<LayoutError>);
+
+impl From<LayoutError> for LayoutErrors {
+    fn from(error: LayoutError) -> Self {
+        Self(vec![error])
+    }
+}
+
+impl fmt::Display for LayoutErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.iter().join("\n"))
+    }
+}
+
+impl std::error::Error for LayoutErrors {}
+
+#[derive(Copy, Clone, Debug)]
+struct DominoValuesGenerator {
+    next: domino::Values,
+    set: DominoSet,
+}
+
+impl DominoValuesGenerator {
+    fn new(set: DominoSet) -> Self {
+        DominoValuesGenerator {
+            next: domino::Values { head: 0, tail: 0 },
+            set,
+        }
+    }
+}
+
+impl Iterator for DominoValuesGenerator {
+    type Item = domino::Values;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let return_value = self.next;
+        if return_value.head > self.set.max_value {
+            return None;
+        }
+        if self.next.tail >= self.next.head {
+            self.next.head += 1;
+            self.next.tail = 0;
+        } else {
+            self.next.tail += 1;
+        }
+        Some(return_value)
+    }
+}
+
+fn orientation_of(character: char) -> Option<domino::Orientation> {
+    match character {
+        '|' => Some(domino::Orientation::Vertical),
+        '-' => Some(domino::Orientation::Horizontal),
+        _ => None,
+    }
+}
+
+struct Grid(Vec<Vec<char>>);
+
+impl Grid {
+    fn parse(input: &str) -> Self {
+        Self(
+            input
+                .split('\n')
+                .map(|line| line.chars().collect_vec())
+                .collect_vec(),
+        )
+    }
+
+    fn width(&self) -> board::Coord {
+        self.0.iter().map(Vec::len).max().unwrap_or(0) as board::Coord
+    }
+
+    fn height(&self) -> board::Coord {
+        self.0.len() as board::Coord
+    }
+
+    fn character_at(&self, position: board::Position) -> Option<char> {
+        let line = self.0.get(position.y as usize)?;
+        line.get(position.x as usize).copied()
+    }
+
+    fn orientation_at(&self, position: board::Position) -> Option<domino::Orientation> {
+        self.character_at(position).and_then(orientation_of)
+    }
+
+    fn has_dominoes(&self) -> bool {
+        self.0
+            .iter()
+            .flatten()
+            .any(|c| orientation_of(*c).is_some())
+    }
+
+    /// Half of the domino at `position` has no partner: report it with the reason.
+    fn unpaired_half(&self, position: board::Position) -> LayoutError {
+        let character = self.character_at(position);
+        let orientation = character.and_then(orientation_of).unwrap();
+        let partner = Domino {
+            values: domino::Values::default(),
+            position,
+            orientation,
+        }
+        .tail_position();
+        let kind = match self.orientation_at(partner) {
+            Some(_) => LayoutErrorKind::InconsistentOrientation,
+            None => LayoutErrorKind::DanglingHalf,
+        };
+        LayoutError::at(position, character, kind)
+    }
+}
+
+/// Check that every `-` and `|` of the layout pairs up into exactly one domino.
+///
+/// Halves are paired the same way the board is built: runs of `-` from left to right, runs of `|`
+/// from top to bottom. Unlike building the board, this goes through the whole layout and returns
+/// every problem found.
+pub fn validate_layout(input: &str) -> Vec<LayoutError> {
+    let grid = Grid::parse(input);
+    if !grid.has_dominoes() {
+        let start = board::Position { x: 0, y: 0 };
+        return vec![LayoutError::at(start, None, LayoutErrorKind::EmptyInput)];
+    }
+    let mut errors = Vec::new();
+    let mut check_run = |cells: &mut dyn Iterator<Item = board::Position>,
+                         orientation: domino::Orientation| {
+        let mut run_length = 0;
+        let mut last_in_run = None;
+        for position in cells.chain(std::iter::once(board::Position {
+            x: board::Coord::MAX,
+            y: board::Coord::MAX,
+        })) {
+            if grid.orientation_at(position) == Some(orientation) {
+                run_length += 1;
+                last_in_run = Some(position);
+            } else {
+                if run_length % 2 == 1 {
+                    errors.push(grid.unpaired_half(last_in_run.unwrap()));
+                }
+                run_length = 0;
+            }
+        }
+    };
+    for y in 0..grid.height() {
+        let mut row = (0..grid.width()).map(|x| board::Position { x, y });
+        check_run(&mut row, domino::Orientation::Horizontal);
+    }
+    for x in 0..grid.width() {
+        let mut column = (0..grid.height()).map(|y| board::Position { x, y });
+        check_run(&mut column, domino::Orientation::Vertical);
+    }
+    errors.sort_by_key(|error| (error.line, error.column));
+    errors
+}
+
+struct Generator {
+    grid: Grid,
+    board: Board,
+}
+
+impl Generator {
+    fn prepare(input: &str) -> Result<Self, LayoutErrors> {
+        let errors = validate_layout(input);
+        if !errors.is_empty() {
+            return Err(LayoutErrors(errors));
+        }
+        let grid = Grid::parse(input);
+        let board = Board::new(grid.width(), grid.height());
+        Ok(Self { grid, board })
+    }
+
+    /// Place all dominoes of the layout, without dealing their values yet.
+    fn build(input: &str, set: DominoSet) -> Result<Self, LayoutErrors> {
+        let mut generator = Self::prepare(input)?;
+        generator.place_dominoes()?;
+        generator.check_set_size(set)?;
+        Ok(generator)
+    }
+
+    fn place_dominoes(&mut self) -> Result<(), LayoutError> {
+        for row in 0..self.grid.0.len() {
+            for col in 0..self.grid.0[row].len() {
+                let position = board::Position {
+                    x: col as board::Coord,
+                    y: row as board::Coord,
+                };
+                let char = self.grid.0[row][col];
+                if let Some(orientation) = orientation_of(char) {
+                    self.fill_tile(position, orientation)
+                        .map_err(|kind| LayoutError::at(position, Some(char), kind))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn fill_tile(
+        &mut self,
+        position: board::Position,
+        orientation: domino::Orientation,
+    ) -> Result<(), LayoutErrorKind> {
+        match self.board.tile(position) {
+            Tile::Empty => {
+                let domino = Domino {
+                    values: domino::Values::default(),
+                    position,
+                    orientation,
+                };
+                match self.grid.orientation_at(domino.tail_position()) {
+                    Some(tail_orientation) if tail_orientation == orientation => {
+                        self.board
+                            .try_put_domino(domino)
+                            .map_err(|_| LayoutErrorKind::InconsistentOrientation)?;
+                    }
+                    Some(_) => return Err(LayoutErrorKind::InconsistentOrientation),
+                    None => return Err(LayoutErrorKind::DanglingHalf),
+                }
+            }
+            Tile::Head(id) | Tile::Tail(id) => {
+                if self.board.dominoes()[&id].orientation != orientation {
+                    return Err(LayoutErrorKind::InconsistentOrientation);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn check_set_size(&self, set: DominoSet) -> Result<(), LayoutError> {
+        let available = set.domino_count();
+        match self.board.dominoes().values().nth(available) {
+            Some(first_excessive) => Err(LayoutError::at(
+                first_excessive.position,
+                self.grid.character_at(first_excessive.position),
+                LayoutErrorKind::TooManyDominoes { available },
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The values as they appear in the set, with the greater one first.
+fn in_set_order(values: domino::Values) -> domino::Values {
+    if values.head >= values.tail {
+        values
+    } else {
+        values.swapped()
+    }
+}
+
+/// Set the values of dominoes given in the values grid, returning their ids.
+fn fix_values(
+    board: &mut Board,
+    values: &str,
+    set: DominoSet,
+) -> Result<BTreeSet<domino::Id>, LayoutErrors> {
+    let grid = Grid::parse(values);
+    let value_at = |position| {
+        let character = grid.character_at(position);
+        match character.and_then(|c| c.to_digit(16)) {
+            Some(value) if value <= set.max_value as u32 => Ok(Some(value as domino::Value)),
+            Some(_) => Err(LayoutError::at(
+                position,
+                character,
+                LayoutErrorKind::ValueOutOfRange { max: set.max_value },
+            )),
+            None => Ok(None),
+        }
+    };
+    let missing = |position| {
+        let character = grid.character_at(position);
+        LayoutError::at(position, character, LayoutErrorKind::MissingValue)
+    };
+    let mut errors = Vec::new();
+    let mut fixed = BTreeSet::new();
+    let mut used = HashSet::new();
+    let dominoes = board.dominoes().clone();
+    for (id, domino) in dominoes {
+        let (head_position, tail_position) = (domino.position, domino.tail_position());
+        match (value_at(head_position), value_at(tail_position)) {
+            (Ok(Some(head)), Ok(Some(tail))) => {
+                let values = domino::Values { head, tail };
+                if used.insert(in_set_order(values)) {
+                    *board.domino_values_mut(id) = values;
+                    fixed.insert(id);
+                } else {
+                    let character = grid.character_at(head_position);
+                    let kind = LayoutErrorKind::DuplicateDomino;
+                    errors.push(LayoutError::at(head_position, character, kind));
+                }
+            }
+            (Ok(None), Ok(None)) => {}
+            (Ok(Some(_)), Ok(None)) => errors.push(missing(tail_position)),
+            (Ok(None), Ok(Some(_))) => errors.push(missing(head_position)),
+            (head, tail) => errors.extend(head.err().into_iter().chain(tail.err())),
+        }
+    }
+    if errors.is_empty() {
+        Ok(fixed)
+    } else {
+        errors.sort_by_key(|error| (error.line, error.column));
+        Err(LayoutErrors(errors))
+    }
+}
+
+/// Deal values to all dominoes but the `fixed` ones, from the part of the set they do not use.
+fn assign_values(board: &mut Board, seed: Seed, set: DominoSet, fixed: &BTreeSet<domino::Id>) {
+    let mut ids = board
+        .dominoes()
+        .keys()
+        .filter(|id| !fixed.contains(id))
+        .cloned()
+        .collect_vec();
+    let used: HashSet<domino::Values> = fixed
+        .iter()
+        .map(|id| in_set_order(board.dominoes()[id].values))
+        .collect();
+    let available = set.values().filter(|values| !used.contains(values));
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    shuffle(&mut ids, &mut rng);
+    for (id, values) in ids.iter().zip(available) {
+        *board.domino_values_mut(*id) = if rng.next_u32() & 1 == 1 {
+            values.swapped()
+        } else {
+            values
+        }
+    }
+}
+
+/// Fisher-Yates shuffle which depends only on the raw output of `rng`.
+fn shuffle<T>(items: &mut [T], rng: &mut impl RngCore) {
+    for i in (1..items.len()).rev() {
+        let j = (rng.next_u64() % (i as u64 + 1)) as usize;
+        items.swap(i, j);
+    }
+}
+
+pub fn generate_from_string(string: &str) -> Result<Board, LayoutErrors> {
+    generate_from_string_with_seed(string, rand::random())
+}
+
+/// Build the board, dealing values from the double-six set.
+pub fn generate_from_string_with_seed(string: &str, seed: Seed) -> Result<Board, LayoutErrors> {
+    let set = DominoSet::DOUBLE_SIX;
+    let mut board = Generator::build(string, set)?.board;
+    assign_values(&mut board, seed, set, &BTreeSet::new());
+    Ok(board)
+}
+
+/// Build the board with the values given in the `values` grid, dealing the rest from `seed`.
+pub fn generate_with_values(
+    layout: &str,
+    values: &str,
+    set: DominoSet,
+    seed: Seed,
+) -> Result<Board, LayoutErrors> {
+    let mut board = Generator::build(layout, set)?.board;
+    let fixed = fix_values(&mut board, values, set)?;
+    assign_values(&mut board, seed, set, &fixed);
+    Ok(board)
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn domino_values_generator() {
+        let generated = DominoSet::DOUBLE_SIX
+            .values()
+            .take(10)
+            .map(|domino::Values { head, tail }| (head, tail))
+            .collect_vec();
+        assert_eq!(
+            generated,
+            [
+                (0, 0),
+                (1, 0),
+                (1, 1),
+                (2, 0),
+                (2, 1),
+                (2, 2),
+                (3, 0),
+                (3, 1),
+                (3, 2),
+                (3, 3)
+            ]
+        )
+    }
+
+    #[test]
+    fn generate_single_horizontal() {
+        let input = "\n--\n";
+        let board = generate_from_string(input).unwrap();
+        assert_eq!(board.width(), 2);
+        assert_eq!(board.height(), 3);
+        let expected_domino = Domino {
+            values: (0, 0).into(),
+            position: (0, 1).into(),
+            orientation: domino::Orientation::Horizontal,
+        };
+        assert_eq!(
+            board.dominoes().values().collect_vec(),
+            vec![&expected_domino]
+        )
+    }
+    #[test]
+    fn generate_single_vertical() {
+        let input = " |\n |\n";
+        let board = generate_from_string(input).unwrap();
+        assert_eq!(board.width(), 2);
+        assert_eq!(board.height(), 3);
+        let expected_domino = Domino {
+            values: (0, 0).into(),
+            position: (1, 0).into(),
+            orientation: domino::Orientation::Vertical,
+        };
+        assert_eq!(
+            board.dominoes().values().collect_vec(),
+            vec![&expected_domino]
+        )
+    }
+
+    fn assert_layout_errors(
+        input: &str,
+        expected: &[((usize, usize), Option<char>, LayoutErrorKind)],
+    ) {
+        let expected = expected
+            .iter()
+            .map(|((line, column), character, kind)| LayoutError {
+                line: *line,
+                column: *column,
+                character: *character,
+                kind: *kind,
+            })
+            .collect_vec();
+        assert_eq!(validate_layout(input), expected);
+        assert_eq!(
+            generate_from_string(input).unwrap_err(),
+            LayoutErrors(expected)
+        );
+    }
+
+    #[test]
+    fn error_on_inconsistency1() {
+        let input = "\n\
+        --|\n\
+        |--\n\
+        |--
+        ";
+        let kind = LayoutErrorKind::InconsistentOrientation;
+        assert_layout_errors(input, &[((2, 3), Some('|'), kind)]);
+    }
+
+    #[test]
+    fn error_on_inconsistency2() {
+        let input = "\n\
+        --|\n\
+        ---";
+        assert_layout_errors(
+            input,
+            &[
+                ((2, 3), Some('|'), LayoutErrorKind::InconsistentOrientation),
+                ((3, 3), Some('-'), LayoutErrorKind::DanglingHalf),
+            ],
+        );
+    }
+
+    #[test]
+    fn error_on_dangling_half() {
+        let kind = LayoutErrorKind::DanglingHalf;
+        assert_layout_errors("-", &[((1, 1), Some('-'), kind)]);
+        assert_layout_errors("--\n|", &[((2, 1), Some('|'), kind)]);
+        assert_layout_errors(
+            "- -",
+            &[((1, 1), Some('-'), kind), ((1, 3), Some('-'), kind)],
+        );
+    }
+
+    #[test]
+    fn all_problems_are_reported() {
+        let input = "\n\
+        |-- -\n\
+        ----|\n\
+        --|--";
+        assert_layout_errors(
+            input,
+            &[
+                ((2, 1), Some('|'), LayoutErrorKind::InconsistentOrientation),
+                ((2, 5), Some('-'), LayoutErrorKind::DanglingHalf),
+                ((3, 5), Some('|'), LayoutErrorKind::InconsistentOrientation),
+                ((4, 3), Some('|'), LayoutErrorKind::DanglingHalf),
+            ],
+        );
+    }
+
+    #[test]
+    fn error_on_empty_input() {
+        let kind = LayoutErrorKind::EmptyInput;
+        assert_layout_errors("", &[((1, 1), None, kind)]);
+        assert_layout_errors("\n  \n", &[((1, 1), None, kind)]);
+    }
+
+    #[test]
+    fn error_on_too_many_dominoes() {
+        let input = "--".repeat(14) + "\n" + &"--".repeat(15);
+        let kind = LayoutErrorKind::TooManyDominoes { available: 28 };
+        assert_eq!(validate_layout(&input), vec![]);
+        assert_eq!(
+            generate_from_string(&input).unwrap_err(),
+            LayoutErrors(vec![LayoutError {
+                line: 2,
+                column: 29,
+                character: Some('-'),
+                kind
+            }])
+        );
+        assert!(generate_from_string(&"--".repeat(28)).is_ok());
+    }
+
+    #[test]
+    fn several_dominoes() {
+        let input = "\n\
+        --|--|\n\
+        --|--||\n      \
+              |";
+        let board = generate_from_string(input).unwrap();
+        assert_eq!(board.width(), 7);
+        assert_eq!(board.height(), 4);
+        println!(
+            "[{}]",
+            board.dominoes().values().map(ToString::to_string).join(",")
+        );
+
+        let mut expected_values: HashSet<domino::Values> =
+            [(0, 0), (1, 0), (1, 1), (2, 0), (2, 1), (2, 2), (3, 0)]
+                .iter()
+                .map(|x| domino::Values::from(*x))
+                .collect();
+        for domino in board.dominoes().values() {
+            assert!(
+                expected_values.remove(&domino.values)
+                    || expected_values.remove(&domino.values.swapped()),
+                "Didn't expected {} domino",
+                domino
+            );
+        }
+        assert_eq!(
+            expected_values.into_iter().collect_vec(),
+            Vec::<domino::Values>::new()
+        );
+    }
+
+    #[test]
+    fn fixed_values() {
+        let layout = "--|--|\n\
+                      --|--|";
+        let values = "016   \n\
+                      ..2   ";
+        let values_of = |board: &Board| {
+            board
+                .dominoes()
+                .values()
+                .map(|domino| (domino.values.head, domino.values.tail))
+                .collect_vec()
+        };
+        for seed in 0..10 {
+            let board = generate_with_values(layout, values, DominoSet::DOUBLE_SIX, seed).unwrap();
+            let values = values_of(&board);
+            assert_eq!(values[..2], [(0, 1), (6, 2)]);
+            let mut in_set = values
+                .iter()
+                .map(|v| in_set_order((*v).into()))
+                .collect_vec();
+            in_set.sort_by_key(|v| (v.head, v.tail));
+            in_set.dedup();
+            assert_eq!(in_set.len(), values.len());
+        }
+        let board = generate_with_values(layout, "", DominoSet::DOUBLE_SIX, 42).unwrap();
+        let seeded = generate_from_string_with_seed(layout, 42).unwrap();
+        assert_eq!(board.dominoes(), seeded.dominoes());
+    }
+
+    #[test]
+    fn errors_in_fixed_values() {
+        let layout = "--|--|\n\
+                      --|--|";
+        let errors = |values| match generate_with_values(layout, values, DominoSet::DOUBLE_SIX, 0) {
+            Err(LayoutErrors(errors)) => errors
+                .into_iter()
+                .map(|error| (error.line, error.column, error.kind))
+                .collect_vec(),
+            Ok(_) => panic!("Expected errors for values {:?}", values),
+        };
+        assert_eq!(
+            errors("1 3\n 25"),
+            [
+                (1, 2, LayoutErrorKind::MissingValue),
+                (2, 1, LayoutErrorKind::MissingValue)
+            ]
+        );
+        let kind = LayoutErrorKind::ValueOutOfRange { max: 6 };
+        assert_eq!(errors("78"), [(1, 1, kind), (1, 2, kind)]);
+        assert_eq!(errors("12 21"), [(1, 4, LayoutErrorKind::DuplicateDomino)]);
+    }
+
+    #[test]
+    fn domino_sets() {
+        for (set, count) in [
+            (DominoSet::DOUBLE_SIX, 28),
+            (DominoSet::DOUBLE_NINE, 55),
+            (DominoSet::DOUBLE_TWELVE, 91),
+            (DominoSet::DOUBLE_FIFTEEN, 136),
+        ] {
+            assert_eq!(set.domino_count(), count);
+            assert_eq!(set.values().count(), count);
+            assert!(set.values().all(|values| set.contains(values)));
+            assert_eq!(set.to_string().parse(), Ok(set));
+        }
+        assert_eq!("Double-Nine".parse(), Ok(DominoSet::DOUBLE_NINE));
+        assert_eq!("double-3".parse(), Ok(DominoSet::new(3).unwrap()));
+        assert!("double-16".parse::<DominoSet>().is_err());
+        assert!(!DominoSet::DOUBLE_SIX.contains((7, 0).into()));
+    }
+
+    #[test]
+    fn dealing_from_larger_sets() {
+        let layout = "--".repeat(20) + "\n" + &"--".repeat(20);
+        assert!(generate_from_string_with_seed(&layout, 0).is_err());
+        let set = DominoSet::DOUBLE_NINE;
+        let board = generate_with_values(&layout, "fe", DominoSet::DOUBLE_FIFTEEN, 0).unwrap();
+        assert_eq!(board.dominoes()[&0].values, (15, 14).into());
+        let board = generate_with_values(&layout, "", set, 0).unwrap();
+        let mut values = board
+            .dominoes()
+            .values()
+            .map(|domino| in_set_order(domino.values))
+            .collect_vec();
+        assert!(values.iter().all(|values| set.contains(*values)));
+        values.sort_by_key(|values| (values.head, values.tail));
+        values.dedup();
+        assert_eq!(values.len(), 40);
+        let kind = LayoutErrorKind::ValueOutOfRange { max: 9 };
+        assert_eq!(
+            generate_with_values(&layout, "a0", set, 0).unwrap_err().0[0].kind,
+            kind
+        );
+    }
+
+    #[test]
+    fn same_seed_gives_same_board() {
+        let input = "--|--|\n\
+                     --|--|";
+        let first = generate_from_string_with_seed(input, 1234).unwrap();
+        let second = generate_from_string_with_seed(input, 1234).unwrap();
+        assert_eq!(first.dominoes(), second.dominoes());
+    }
+
+    #[test]
+    fn seeded_deal_is_stable() {
+        // Seeds are shared between players, so the deal for a given seed must never change.
+        let input = "--|--|\n\
+                     --|--|";
+        let board = generate_from_string_with_seed(input, 42).unwrap();
+        let values = board
+            .dominoes()
+            .values()
+            .map(|domino| (domino.values.head, domino.values.tail))
+            .collect_vec();
+        assert_eq!(values, [(0, 2), (1, 1), (2, 1), (2, 2), (0, 0), (0, 1)]);
+    }
+}
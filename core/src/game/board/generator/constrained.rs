@@ -0,0 +1,258 @@
+//! Deals which meet [`Constraint`]s, found by dealing from successive seeds.
+//!
+//! Deals are not modified to meet the constraints: the seed of the first deal meeting them is
+//! returned, so the board is dealt again from it like any other, and games, saves and replays only
+//! need that seed.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::game::board::generator::level::Level;
+use crate::game::board::generator::Seed;
+use crate::game::board::Tile;
+use crate::game::rules::Rules;
+use crate::game::{solver, Game, Score};
+
+pub const DEFAULT_MAX_ATTEMPTS: usize = 1000;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Constraint {
+    /// The best final score is at least this.
+    MinOptimalScore(Score),
+    /// No explosive domino is in the blast of another at the start.
+    NoAdjacentExplosives,
+    /// At least this many doubles cost points when hit, and only score when blown up.
+    MinPenalisedDoubles(usize),
+}
+
+impl Constraint {
+    pub fn holds(&self, game: &Game) -> bool {
+        match *self {
+            Self::MinOptimalScore(score) => solver::solve(game).score >= score,
+            Self::NoAdjacentExplosives => !has_adjacent_explosives(game),
+            Self::MinPenalisedDoubles(count) => penalised_doubles(game) >= count,
+        }
+    }
+
+    /// Whether checking the constraint solves the game, so it is only worth checking once the
+    /// others hold.
+    fn needs_solver(&self) -> bool {
+        matches!(self, Self::MinOptimalScore(_))
+    }
+}
+
+fn has_adjacent_explosives(game: &Game) -> bool {
+    let (board, rules) = (game.board(), game.rules());
+    board.dominoes().iter().any(|(id, domino)| {
+        let halves = [
+            (domino.position, domino.values.head),
+            (domino.tail_position(), domino.values.tail),
+        ];
+        halves
+            .into_iter()
+            .filter(|(_, value)| rules.is_explosive(*value))
+            .flat_map(|(position, value)| {
+                let shape = rules.explosion_shape(domino.values, value);
+                shape.blasted_positions(board, position)
+            })
+            .any(|position| match board.tile(position) {
+                Tile::Head(other) | Tile::Tail(other) => {
+                    other != *id && game.is_explosive(&board.dominoes()[&other])
+                }
+                Tile::Empty => false,
+            })
+    })
+}
+
+fn penalised_doubles(game: &Game) -> usize {
+    game.dominoes()
+        .values()
+        .filter(|domino| domino.values.head == domino.values.tail && !game.is_explosive(domino))
+        .filter(|domino| game.rules().score(domino.values, false, 0, 0) < 0)
+        .count()
+}
+
+impl fmt::Display for Constraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MinOptimalScore(score) => write!(f, "min-optimal-score={}", score),
+            Self::NoAdjacentExplosives => write!(f, "no-adjacent-explosives"),
+            Self::MinPenalisedDoubles(count) => write!(f, "min-penalised-doubles={}", count),
+        }
+    }
+}
+
+impl FromStr for Constraint {
+    type Err = String;
+
+    /// Parse a constraint as it is displayed, such as `min-optimal-score=80`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid constraint \"{}\"", s);
+        match s.trim().split_once('=') {
+            Some(("min-optimal-score", score)) => score
+                .parse()
+                .map(Self::MinOptimalScore)
+                .map_err(|_| invalid()),
+            Some(("min-penalised-doubles", count)) => count
+                .parse()
+                .map(Self::MinPenalisedDoubles)
+                .map_err(|_| invalid()),
+            None if s.trim() == "no-adjacent-explosives" => Ok(Self::NoAdjacentExplosives),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// No deal tried met all the constraints.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ConstraintError {
+    pub attempts: usize,
+    /// How many of the deals tried failed each constraint, in the order they were given.
+    /// Constraints needing the solver are only checked on deals meeting all the others.
+    pub failures: Vec<(Constraint, usize)>,
+}
+
+impl fmt::Display for ConstraintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no deal out of {} met the constraints", self.attempts)?;
+        let failed = self.failures.iter().filter(|(_, failures)| *failures > 0);
+        for (index, (constraint, failures)) in failed.enumerate() {
+            let separator = if index == 0 { ": " } else { ", " };
+            write!(f, "{}{} failed {} times", separator, constraint, failures)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConstraintError {}
+
+/// The first of `seed` and the seeds following it, trying at most `max_attempts`, which deals the
+/// level so that the game played with `rules` meets all the `constraints`.
+pub fn find_seed(
+    level: &Level,
+    rules: &Rules,
+    constraints: &[Constraint],
+    max_attempts: usize,
+    seed: Seed,
+) -> Result<Seed, ConstraintError> {
+    let mut failures: Vec<(Constraint, usize)> = constraints.iter().map(|c| (*c, 0)).collect();
+    // Levels fixing all values deal the same board from every seed.
+    let attempts = if level.deals_values() {
+        max_attempts
+    } else {
+        max_attempts.min(1)
+    };
+    for attempt in 0..attempts {
+        let seed = seed.wrapping_add(attempt as Seed);
+        let game = Game::new(level.deal(seed), rules.clone());
+        let mut met = true;
+        for needs_solver in [false, true] {
+            let checked = failures
+                .iter_mut()
+                .filter(|(constraint, _)| constraint.needs_solver() == needs_solver);
+            for (constraint, failed) in checked {
+                if !constraint.holds(&game) {
+                    *failed += 1;
+                    met = false;
+                }
+            }
+            if !met {
+                break;
+            }
+        }
+        if met {
+            return Ok(seed);
+        }
+    }
+    Err(ConstraintError { attempts, failures })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn game_of(level: &str) -> Game {
+        Game::new_from_level(&Level::parse(level).unwrap())
+    }
+
+    #[test]
+    fn constraints() {
+        let touching = game_of("[layout]\n----\n[values]\n1002");
+        let apart = game_of("[layout]\n--  --\n[values]\n01  02");
+        assert!(!Constraint::NoAdjacentExplosives.holds(&touching));
+        assert!(Constraint::NoAdjacentExplosives.holds(&apart));
+
+        // The blank double explodes instead of costing points.
+        let doubles = game_of("[layout]\n------\n[values]\n112200");
+        assert!(Constraint::MinPenalisedDoubles(2).holds(&doubles));
+        assert!(!Constraint::MinPenalisedDoubles(3).holds(&doubles));
+
+        assert!(Constraint::MinOptimalScore(3).holds(&apart));
+        assert!(!Constraint::MinOptimalScore(4).holds(&apart));
+    }
+
+    #[test]
+    fn parsing() {
+        for constraint in [
+            Constraint::MinOptimalScore(-5),
+            Constraint::NoAdjacentExplosives,
+            Constraint::MinPenalisedDoubles(3),
+        ] {
+            assert_eq!(constraint.to_string().parse(), Ok(constraint));
+        }
+        assert!("min-optimal-score".parse::<Constraint>().is_err());
+        assert!("min-penalised-doubles=-1".parse::<Constraint>().is_err());
+        assert!("no-adjacent-explosives=1".parse::<Constraint>().is_err());
+    }
+
+    #[test]
+    fn finding_seeds() {
+        let level = Level::parse("[layout]\n----  ----\n\n----  ----").unwrap();
+        let rules = Rules::classic();
+        assert_eq!(find_seed(&level, &rules, &[], 10, 7), Ok(7));
+
+        let constraints = [
+            Constraint::NoAdjacentExplosives,
+            Constraint::MinPenalisedDoubles(1),
+        ];
+        let seed = find_seed(&level, &rules, &constraints, 100, 0).unwrap();
+        let game = Game::new_from_level_seeded(&level, seed);
+        assert!(constraints.iter().all(|constraint| constraint.holds(&game)));
+        assert_eq!(find_seed(&level, &rules, &constraints, 100, seed), Ok(seed));
+    }
+
+    #[test]
+    fn unmet_constraints() {
+        let level = Level::parse("[layout]\n--|--|\n--|--|").unwrap();
+        let rules = Rules::classic();
+        let constraints = [
+            Constraint::NoAdjacentExplosives,
+            Constraint::MinOptimalScore(1000),
+        ];
+        // The blanks of the first dominoes of the set always touch, so the solver never runs.
+        let error = find_seed(&level, &rules, &constraints, 5, 0).unwrap_err();
+        assert_eq!(error.attempts, 5);
+        assert_eq!(
+            error.failures,
+            [
+                (Constraint::NoAdjacentExplosives, 5),
+                (Constraint::MinOptimalScore(1000), 0)
+            ]
+        );
+        assert_eq!(
+            error.to_string(),
+            "no deal out of 5 met the constraints: no-adjacent-explosives failed 5 times"
+        );
+
+        let constraints = [
+            Constraint::MinOptimalScore(1000),
+            Constraint::MinPenalisedDoubles(1),
+        ];
+        let error = find_seed(&level, &rules, &constraints, 5, 0).unwrap_err();
+        assert_eq!(error.failures[0], (Constraint::MinOptimalScore(1000), 5));
+
+        let fixed = Level::parse("[layout]\n----\n[values]\n1234").unwrap();
+        let error = find_seed(&fixed, &rules, &constraints, 5, 0).unwrap_err();
+        assert_eq!(error.attempts, 1);
+    }
+}
@@ -0,0 +1,591 @@
+//! Level files: a header of `key: value` lines, then the layout and optionally the values of its
+//! dominoes.
+//!
+//! ```text
+//! # Lines starting with '#' are comments.
+//! name: Two by two
+//! author: Somebody
+//! difficulty: easy
+//! set: double-six
+//! seed: 42
+//! par: 3
+//!
+//! [layout]
+//! --|
+//! --|
+//!
+//! [values]
+//! 125
+//! 346
+//! ```
+//!
+//! All header keys are optional; the set defaults to double-six. The `[values]` section repeats the
+//! layout with the pips of each domino half, as a hexadecimal digit, written where the half is.
+//! Dominoes left out of it, or all of them when there is no such section, are dealt from `seed`, or
+//! from a random seed when the level has none.
+//!
+//! A [`LevelPack`] is either a directory of `*.level` files, played in the order of their names,
+//! or a single file with levels separated by `===` lines.
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::game::board::generator::{
+    assign_values, fix_values, DominoSet, Generator, LayoutErrors, Seed,
+};
+use crate::game::board::Board;
+use crate::game::domino;
+use crate::game::Score;
+
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Easy => "easy",
+            Self::Medium => "medium",
+            Self::Hard => "hard",
+            Self::Expert => "expert",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "easy" => Ok(Self::Easy),
+            "medium" => Ok(Self::Medium),
+            "hard" => Ok(Self::Hard),
+            "expert" => Ok(Self::Expert),
+            _ => Err(format!("unknown difficulty \"{}\"", s)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LevelInfo {
+    pub name: String,
+    pub author: Option<String>,
+    pub difficulty: Option<Difficulty>,
+    pub set: DominoSet,
+    pub seed: Option<Seed>,
+    pub par: Option<Score>,
+}
+
+#[derive(Debug)]
+pub enum LevelError {
+    Io(io::Error),
+    /// Malformed header or section line. Lines are counted from 1.
+    Syntax {
+        line: usize,
+        message: String,
+    },
+    MissingLayout,
+    /// Problems in the layout or values, with lines counted from the start of the file.
+    Layout(LayoutErrors),
+    EmptyPack,
+    /// Problem with one level of a pack.
+    InPack {
+        level: String,
+        error: Box<LevelError>,
+    },
+}
+
+impl fmt::Display for LevelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "{}", error),
+            Self::Syntax { line, message } => write!(f, "line {}: {}", line, message),
+            Self::MissingLayout => write!(f, "the level has no [layout] section"),
+            Self::Layout(errors) => write!(f, "{}", errors),
+            Self::EmptyPack => write!(f, "the level pack has no levels"),
+            Self::InPack { level, error } => write!(f, "{}: {}", level, error),
+        }
+    }
+}
+
+impl std::error::Error for LevelError {}
+
+impl From<io::Error> for LevelError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Level {
+    pub info: LevelInfo,
+    pub layout: String,
+    pub values: Option<String>,
+    /// The dominoes of the layout, with values only for the `fixed` ones.
+    board: Board,
+    fixed: BTreeSet<domino::Id>,
+}
+
+/// Lines of a section, and the number of file lines before its first one.
+struct Section<'a> {
+    start: usize,
+    lines: Vec<&'a str>,
+}
+
+impl Section<'_> {
+    fn text(&self) -> String {
+        let mut lines = self.lines.as_slice();
+        while let [rest @ .., last] = lines {
+            if !last.trim().is_empty() {
+                break;
+            }
+            lines = rest;
+        }
+        lines.join("\n")
+    }
+
+    fn to_file_lines(&self, mut errors: LayoutErrors) -> LevelError {
+        for error in &mut errors.0 {
+            error.line += self.start;
+        }
+        LevelError::Layout(errors)
+    }
+}
+
+impl Level {
+    pub fn parse(input: &str) -> Result<Self, LevelError> {
+        let mut info = LevelInfo::default();
+        let mut layout: Option<Section> = None;
+        let mut values: Option<Section> = None;
+        let mut seen_keys = Vec::new();
+        let mut in_values = None;
+        for (index, line) in input.lines().enumerate() {
+            let syntax_error = |message: String| LevelError::Syntax {
+                line: index + 1,
+                message,
+            };
+            let trimmed = line.trim();
+            let is_values = match trimmed {
+                "[layout]" => Some(false),
+                "[values]" => Some(true),
+                _ if trimmed.starts_with('[') && trimmed.ends_with(']') => {
+                    return Err(syntax_error(format!("unknown section {}", trimmed)));
+                }
+                _ => None,
+            };
+            if let Some(is_values) = is_values {
+                let section = if is_values { &mut values } else { &mut layout };
+                if section.is_some() {
+                    return Err(syntax_error(format!("repeated section {}", trimmed)));
+                }
+                *section = Some(Section {
+                    start: index + 1,
+                    lines: Vec::new(),
+                });
+                in_values = Some(is_values);
+                continue;
+            }
+            match in_values {
+                Some(false) => layout.as_mut().unwrap().lines.push(line),
+                Some(true) => values.as_mut().unwrap().lines.push(line),
+                None => {
+                    let key = parse_header_line(&mut info, line).map_err(syntax_error)?;
+                    if let Some(key) = key {
+                        if seen_keys.contains(&key) {
+                            return Err(syntax_error(format!("repeated key \"{}\"", key)));
+                        }
+                        seen_keys.push(key);
+                    }
+                }
+            }
+        }
+        let layout = layout.ok_or(LevelError::MissingLayout)?;
+        let layout_text = layout.text();
+        let mut board = Generator::build(&layout_text, info.set)
+            .map_err(|errors| layout.to_file_lines(errors))?
+            .board;
+        let (values_text, fixed) = match &values {
+            Some(values) => {
+                let text = values.text();
+                let fixed = fix_values(&mut board, &text, info.set)
+                    .map_err(|errors| values.to_file_lines(errors))?;
+                (Some(text), fixed)
+            }
+            None => (None, BTreeSet::new()),
+        };
+        Ok(Self {
+            info,
+            layout: layout_text,
+            values: values_text,
+            board,
+            fixed,
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, LevelError> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Whether some dominoes get their values from the seed.
+    pub fn deals_values(&self) -> bool {
+        self.fixed.len() < self.board.dominoes().len()
+    }
+
+    /// The board of this level, with the values it does not fix dealt from `seed`.
+    pub fn deal(&self, seed: Seed) -> Board {
+        let mut board = self.board.clone();
+        assign_values(&mut board, seed, self.info.set, &self.fixed);
+        board
+    }
+}
+
+/// Writes the level in the format it is parsed from, without comments.
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let info = &self.info;
+        if !info.name.is_empty() {
+            writeln!(f, "name: {}", info.name)?;
+        }
+        if let Some(author) = &info.author {
+            writeln!(f, "author: {}", author)?;
+        }
+        if let Some(difficulty) = info.difficulty {
+            writeln!(f, "difficulty: {}", difficulty)?;
+        }
+        if info.set != DominoSet::default() {
+            writeln!(f, "set: {}", info.set)?;
+        }
+        if let Some(seed) = info.seed {
+            writeln!(f, "seed: {}", seed)?;
+        }
+        if let Some(par) = info.par {
+            writeln!(f, "par: {}", par)?;
+        }
+        writeln!(f, "[layout]\n{}", self.layout)?;
+        if let Some(values) = &self.values {
+            writeln!(f, "[values]\n{}", values)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct LevelPack {
+    pub name: String,
+    pub levels: Vec<Level>,
+}
+
+const PACK_SEPARATOR: &str = "===";
+
+impl LevelPack {
+    /// Parse a pack file, with levels separated by `===` lines.
+    pub fn parse(name: &str, input: &str) -> Result<Self, LevelError> {
+        let mut sources = vec![String::new()];
+        for line in input.lines() {
+            if line.trim() == PACK_SEPARATOR {
+                sources.push(String::new());
+            } else {
+                let source = sources.last_mut().unwrap();
+                source.push_str(line);
+                source.push('\n');
+            }
+        }
+        let levels = sources
+            .iter()
+            .enumerate()
+            .filter(|(_, source)| !source.trim().is_empty())
+            .map(|(index, source)| {
+                Level::parse(source).map_err(|error| LevelError::InPack {
+                    level: format!("level {}", index + 1),
+                    error: Box::new(error),
+                })
+            });
+        Self::new(name, levels)
+    }
+
+    /// Load a pack from a directory of `*.level` files or from a single pack file.
+    pub fn load(path: &Path) -> Result<Self, LevelError> {
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        if !path.is_dir() {
+            return Self::parse(&name, &std::fs::read_to_string(path)?);
+        }
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(path)? {
+            let file = entry?.path();
+            if file
+                .extension()
+                .is_some_and(|extension| extension == "level")
+            {
+                files.push(file);
+            }
+        }
+        files.sort();
+        let levels = files.iter().map(|file| {
+            Level::load(file).map_err(|error| LevelError::InPack {
+                level: file.display().to_string(),
+                error: Box::new(error),
+            })
+        });
+        Self::new(&name, levels)
+    }
+
+    /// How many levels, from the first, can be played: every completed level unlocks the next.
+    pub fn unlocked_levels(&self, is_completed: impl Fn(&Level) -> bool) -> usize {
+        let completed = self.levels.iter().take_while(|level| is_completed(level));
+        (completed.count() + 1).min(self.levels.len())
+    }
+
+    fn new(
+        name: &str,
+        levels: impl Iterator<Item = Result<Level, LevelError>>,
+    ) -> Result<Self, LevelError> {
+        let levels = levels.collect::<Result<Vec<_>, _>>()?;
+        if levels.is_empty() {
+            return Err(LevelError::EmptyPack);
+        }
+        Ok(Self {
+            name: name.to_owned(),
+            levels,
+        })
+    }
+}
+
+/// Parse a header line into `info`, returning the key it set, if any.
+fn parse_header_line(info: &mut LevelInfo, line: &str) -> Result<Option<String>, String> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(None);
+    }
+    let (key, value) = line
+        .split_once(':')
+        .ok_or_else(|| "expected \"key: value\" or a [layout] section".to_owned())?;
+    let (key, value) = (key.trim(), value.trim());
+    let invalid = |_| format!("invalid {} \"{}\"", key, value);
+    match key {
+        "name" => info.name = value.to_owned(),
+        "author" => info.author = Some(value.to_owned()),
+        "difficulty" => info.difficulty = Some(value.parse()?),
+        "set" => info.set = value.parse()?,
+        "seed" => info.seed = Some(value.parse().map_err(invalid)?),
+        "par" => info.par = Some(value.parse().map_err(invalid)?),
+        _ => return Err(format!("unknown key \"{}\"", key)),
+    }
+    Ok(Some(key.to_owned()))
+}
+
+/// Parse a level and deal its board, from the level's seed or a random one.
+pub fn generate_from_level(input: &str) -> Result<(Board, LevelInfo), LevelError> {
+    let level = Level::parse(input)?;
+    let board = level.deal(level.info.seed.unwrap_or_else(rand::random));
+    Ok((board, level.info))
+}
+
+#[cfg(test)]
+mod test {
+    use itertools::Itertools;
+
+    use super::*;
+    use crate::game::board::generator::{
+        generate_from_string_with_seed, generate_with_values, LayoutError, LayoutErrorKind,
+    };
+    use crate::test_dir::TestDir;
+
+    #[test]
+    fn parsing_header() {
+        let input = "# A comment\n\
+                     name: Two by two\n\
+                     author: Somebody\n\
+                     difficulty: Hard\n\
+                     \n\
+                     seed: 42\n\
+                     par: -3\n\
+                     [layout]\n\
+                     --|\n\
+                     --|\n\
+                     \n";
+        let level = Level::parse(input).unwrap();
+        let expected = LevelInfo {
+            name: "Two by two".to_owned(),
+            author: Some("Somebody".to_owned()),
+            difficulty: Some(Difficulty::Hard),
+            set: DominoSet::DOUBLE_SIX,
+            seed: Some(42),
+            par: Some(-3),
+        };
+        assert_eq!(level.info, expected);
+        assert_eq!(level.layout, "--|\n--|");
+        assert!(level.deals_values());
+        let (board, info) = generate_from_level(input).unwrap();
+        assert_eq!(info, expected);
+        let written = Level::parse(&level.to_string()).unwrap();
+        assert_eq!((written.info, written.layout), (expected, level.layout));
+        let seeded = generate_from_string_with_seed("--|\n--|", 42).unwrap();
+        assert_eq!(board.dominoes(), seeded.dominoes());
+    }
+
+    #[test]
+    fn fixed_values() {
+        let input = "[layout]\n\
+                     --|\n\
+                     --|\n\
+                     \n\
+                     [values]\n\
+                     125\n\
+                     346";
+        let level = Level::parse(input).unwrap();
+        assert!(!level.deals_values());
+        let values = |board: Board| {
+            board
+                .dominoes()
+                .values()
+                .map(|domino| (domino.values.head, domino.values.tail))
+                .collect_vec()
+        };
+        assert_eq!(values(level.deal(1)), [(1, 2), (5, 6), (3, 4)]);
+        assert_eq!(values(level.deal(2)), values(level.deal(1)));
+    }
+
+    #[test]
+    fn larger_sets() {
+        let layout = "--".repeat(30);
+        let input = format!("set: double-nine\n[layout]\n{}\n[values]\n99", layout);
+        let level = Level::parse(&input).unwrap();
+        assert_eq!(level.info.set, DominoSet::DOUBLE_NINE);
+        assert_eq!(level.deal(0).dominoes()[&0].values, (9, 9).into());
+        let written = Level::parse(&level.to_string()).unwrap();
+        assert_eq!(written.deal(0).dominoes(), level.deal(0).dominoes());
+        assert!(Level::parse(&format!("[layout]\n{}", layout)).is_err());
+        assert!(Level::parse(&input.replace("nine", "six")).is_err());
+    }
+
+    #[test]
+    fn header_errors() {
+        let syntax_error_line = |input| match Level::parse(input) {
+            Err(LevelError::Syntax { line, .. }) => line,
+            other => panic!("Expected syntax error, got {:?}", other),
+        };
+        assert_eq!(syntax_error_line("name: a\ncolour: red\n[layout]\n--"), 2);
+        assert_eq!(syntax_error_line("seed: -1\n[layout]\n--"), 1);
+        assert_eq!(syntax_error_line("difficulty: trivial\n[layout]\n--"), 1);
+        assert_eq!(syntax_error_line("set: double-20\n[layout]\n--"), 1);
+        assert_eq!(syntax_error_line("\n\njust text\n[layout]\n--"), 3);
+        assert_eq!(syntax_error_line("par: 1\npar: 2\n[layout]\n--"), 2);
+        assert_eq!(syntax_error_line("[layout]\n--\n[layout]\n--"), 3);
+        assert_eq!(syntax_error_line("[layot]\n--"), 1);
+        assert!(matches!(
+            Level::parse("name: a\n--"),
+            Err(LevelError::Syntax { line: 2, .. })
+        ));
+        assert!(matches!(
+            Level::parse("name: a"),
+            Err(LevelError::MissingLayout)
+        ));
+    }
+
+    #[test]
+    fn errors_are_reported_at_file_lines() {
+        let input = "name: a\n\
+                     [layout]\n\
+                     --|\n\
+                     -- \n\
+                     [values]\n\
+                     123\n\
+                     4x5";
+        let errors = match Level::parse(input) {
+            Err(LevelError::Layout(errors)) => errors,
+            other => panic!("Expected layout errors, got {:?}", other),
+        };
+        let kind = LayoutErrorKind::DanglingHalf;
+        assert_eq!(
+            errors,
+            LayoutErrors(vec![LayoutError {
+                line: 3,
+                column: 3,
+                character: Some('|'),
+                kind
+            }])
+        );
+
+        let input = input.replace("-- \n", "--|\n");
+        let errors = match Level::parse(&input) {
+            Err(LevelError::Layout(errors)) => errors,
+            other => panic!("Expected value errors, got {:?}", other),
+        };
+        let positions = errors
+            .0
+            .iter()
+            .map(|error| (error.line, error.column, error.kind))
+            .collect_vec();
+        assert_eq!(positions, [(7, 2, LayoutErrorKind::MissingValue)]);
+    }
+
+    #[test]
+    fn partially_fixed_values() {
+        let level = Level::parse("seed: 3\n[layout]\n----\n[values]\n..65").unwrap();
+        assert!(level.deals_values());
+        let board = level.deal(3);
+        let last = board
+            .dominoes()
+            .values()
+            .next_back()
+            .map(|domino| domino.values);
+        assert_eq!(last, Some((6, 5).into()));
+        assert_eq!(
+            board.dominoes(),
+            generate_with_values("----", "..65", DominoSet::DOUBLE_SIX, 3)
+                .unwrap()
+                .dominoes()
+        );
+    }
+
+    #[test]
+    fn parsing_packs() {
+        let input = "name: first\n\
+                     [layout]\n\
+                     --\n\
+                     ===\n\
+                     name: second\n\
+                     [layout]\n\
+                     |\n\
+                     |\n\
+                     ===\n";
+        let pack = LevelPack::parse("pack", input).unwrap();
+        let names = pack.levels.iter().map(|level| level.info.name.as_str());
+        assert_eq!(names.collect_vec(), ["first", "second"]);
+        assert_eq!(pack.unlocked_levels(|_| false), 1);
+        assert_eq!(pack.unlocked_levels(|level| level.info.name == "first"), 2);
+        assert_eq!(pack.unlocked_levels(|level| level.info.name == "second"), 1);
+        assert_eq!(pack.unlocked_levels(|_| true), 2);
+
+        match LevelPack::parse("pack", "[layout]\n--\n===\n[layout]\n-") {
+            Err(LevelError::InPack { level, .. }) => assert_eq!(level, "level 2"),
+            other => panic!("Expected error in level 2, got {:?}", other),
+        }
+        assert!(matches!(
+            LevelPack::parse("pack", "\n===\n"),
+            Err(LevelError::EmptyPack)
+        ));
+    }
+
+    #[test]
+    fn loading_pack_directory() {
+        let directory = TestDir::new();
+        std::fs::write(directory.join("02.level"), "name: b\n[layout]\n--").unwrap();
+        std::fs::write(directory.join("01.level"), "name: a\n[layout]\n--").unwrap();
+        std::fs::write(directory.join("notes.txt"), "not a level").unwrap();
+        let pack = LevelPack::load(&directory).unwrap();
+        let names = pack.levels.iter().map(|level| level.info.name.as_str());
+        assert_eq!(names.collect_vec(), ["a", "b"]);
+    }
+}
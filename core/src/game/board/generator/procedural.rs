@@ -0,0 +1,412 @@
+//! Random layouts: dominoes laid on the tiles of a [`Mask`], covering all of them or leaving
+//! holes, instead of a hand-drawn layout.
+//!
+//! Like dealing values, tiling only uses the raw output of a seeded ChaCha8 generator, so a
+//! mask, the [`Options`] and a [`Seed`] always give the same layout.
+
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::collections::BTreeSet;
+use std::fmt;
+
+use crate::game::board::generator::{assign_values, DominoSet, Generator, LayoutErrors, Seed};
+use crate::game::board::{Board, Coord, Position};
+
+/// How many placements a full tiling may try before giving up.
+const MAX_TILING_STEPS: usize = 100_000;
+
+/// The tiles of a rectangle which may hold dominoes.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Mask {
+    width: Coord,
+    height: Coord,
+    tiles: Vec<bool>,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Symmetry {
+    /// The right half mirrors the left one.
+    LeftRight,
+    /// The bottom half mirrors the top one.
+    TopBottom,
+    Both,
+}
+
+impl Mask {
+    pub fn from_fn(width: Coord, height: Coord, contains: impl FnMut(Position) -> bool) -> Self {
+        let tiles = (0..height)
+            .flat_map(|y| (0..width).map(move |x| Position { x, y }))
+            .map(contains)
+            .collect();
+        Self {
+            width,
+            height,
+            tiles,
+        }
+    }
+
+    pub fn rectangle(width: Coord, height: Coord) -> Self {
+        Self::from_fn(width, height, |_| true)
+    }
+
+    /// The border of the rectangle, `thickness` tiles wide.
+    pub fn ring(width: Coord, height: Coord, thickness: Coord) -> Self {
+        Self::from_fn(width, height, |Position { x, y }| {
+            x < thickness || y < thickness || x + thickness >= width || y + thickness >= height
+        })
+    }
+
+    /// The diamond touching the middle of each side of the rectangle.
+    pub fn diamond(width: Coord, height: Coord) -> Self {
+        let (half_width, half_height) = (width as f64 / 2.0, height as f64 / 2.0);
+        Self::from_fn(width, height, |Position { x, y }| {
+            let dx = (x as f64 + 0.5 - half_width).abs() / half_width;
+            let dy = (y as f64 + 0.5 - half_height).abs() / half_height;
+            dx + dy <= 1.0
+        })
+    }
+
+    /// Tiles drawn as `#`, one line per row.
+    pub fn parse(input: &str) -> Self {
+        let lines: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
+        let width = lines.iter().map(Vec::len).max().unwrap_or(0);
+        Self::from_fn(width as Coord, lines.len() as Coord, |Position { x, y }| {
+            lines[y as usize].get(x as usize) == Some(&'#')
+        })
+    }
+
+    /// A random pattern covering about `fill` of the rectangle, mirrored as given.
+    pub fn random_symmetric(
+        width: Coord,
+        height: Coord,
+        fill: f64,
+        symmetry: Symmetry,
+        seed: Seed,
+    ) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let random = Self::from_fn(width, height, |_| chance(&mut rng, fill));
+        let mirror_x = symmetry != Symmetry::TopBottom;
+        let mirror_y = symmetry != Symmetry::LeftRight;
+        Self::from_fn(width, height, |Position { mut x, mut y }| {
+            if mirror_x && x >= width.div_ceil(2) {
+                x = width - 1 - x;
+            }
+            if mirror_y && y >= height.div_ceil(2) {
+                y = height - 1 - y;
+            }
+            random.contains(Position { x, y })
+        })
+    }
+
+    pub fn width(&self) -> Coord {
+        self.width
+    }
+
+    pub fn height(&self) -> Coord {
+        self.height
+    }
+
+    pub fn contains(&self, position: Position) -> bool {
+        position.x < self.width
+            && position.y < self.height
+            && self.tiles[(position.y * self.width + position.x) as usize]
+    }
+
+    pub fn tile_count(&self) -> usize {
+        self.tiles.iter().filter(|tile| **tile).count()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Options {
+    /// The part of the mask to cover, between 0 and 1. Only 1 requires tiling the whole mask;
+    /// below it, tiles are left empty at random, and wherever no domino fits.
+    pub density: f64,
+    /// The chance of laying a domino horizontally when it fits both ways.
+    pub horizontal_bias: f64,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            density: 1.0,
+            horizontal_bias: 0.5,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ProceduralError {
+    /// No full tiling of the mask was found.
+    NoTiling,
+    /// The layout does not suit the set, as when it has too many dominoes.
+    Layout(LayoutErrors),
+}
+
+impl fmt::Display for ProceduralError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoTiling => write!(f, "the mask cannot be covered with dominoes"),
+            Self::Layout(errors) => write!(f, "{}", errors),
+        }
+    }
+}
+
+impl std::error::Error for ProceduralError {}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Cell {
+    Outside,
+    Free,
+    Hole,
+    Horizontal,
+    Vertical,
+}
+
+struct Tiling<'a> {
+    width: Coord,
+    cells: Vec<Cell>,
+    options: &'a Options,
+    rng: ChaCha8Rng,
+    steps: usize,
+}
+
+impl Tiling<'_> {
+    fn index(&self, x: Coord, y: Coord) -> usize {
+        (y * self.width + x) as usize
+    }
+
+    fn is_free(&self, x: Coord, y: Coord) -> bool {
+        x < self.width && self.cells.get(self.index(x, y)) == Some(&Cell::Free)
+    }
+
+    /// The free tiles the domino with its head at `start` can take, in the order to try them.
+    fn placements(&mut self, start: usize) -> Vec<(Cell, usize)> {
+        let (x, y) = (start as Coord % self.width, start as Coord / self.width);
+        let mut placements = Vec::new();
+        if self.is_free(x + 1, y) {
+            placements.push((Cell::Horizontal, start + 1));
+        }
+        if self.is_free(x, y + 1) {
+            placements.push((Cell::Vertical, self.index(x, y + 1)));
+        }
+        if placements.len() == 2 && !chance(&mut self.rng, self.options.horizontal_bias) {
+            placements.reverse();
+        }
+        placements
+    }
+
+    fn place(&mut self, head: usize, (cell, tail): (Cell, usize)) {
+        self.cells[head] = cell;
+        self.cells[tail] = cell;
+    }
+
+    /// Cover every free tile from `start`, backtracking on dead ends.
+    fn tile_fully(&mut self, start: usize) -> bool {
+        let head = match (start..self.cells.len()).find(|i| self.cells[*i] == Cell::Free) {
+            Some(head) => head,
+            None => return true,
+        };
+        for placement in self.placements(head) {
+            self.steps += 1;
+            if self.steps > MAX_TILING_STEPS {
+                return false;
+            }
+            self.place(head, placement);
+            if self.tile_fully(head + 1) {
+                return true;
+            }
+            self.cells[head] = Cell::Free;
+            self.cells[placement.1] = Cell::Free;
+        }
+        false
+    }
+
+    fn tile_with_holes(&mut self) {
+        for head in 0..self.cells.len() {
+            if self.cells[head] != Cell::Free {
+                continue;
+            }
+            let placement = if chance(&mut self.rng, self.options.density) {
+                self.placements(head).first().copied()
+            } else {
+                None
+            };
+            match placement {
+                Some(placement) => self.place(head, placement),
+                None => self.cells[head] = Cell::Hole,
+            }
+        }
+    }
+}
+
+/// Lay dominoes on the mask, returning the layout as drawn in level files, with a line of the
+/// mask's width for each of its rows.
+pub fn generate_layout(
+    mask: &Mask,
+    options: &Options,
+    seed: Seed,
+) -> Result<String, ProceduralError> {
+    let cells = mask
+        .tiles
+        .iter()
+        .map(|tile| if *tile { Cell::Free } else { Cell::Outside })
+        .collect();
+    let mut tiling = Tiling {
+        width: mask.width,
+        cells,
+        options,
+        rng: ChaCha8Rng::seed_from_u64(seed),
+        steps: 0,
+    };
+    if options.density < 1.0 {
+        tiling.tile_with_holes();
+    } else if !has_balanced_colors(mask) || !tiling.tile_fully(0) {
+        return Err(ProceduralError::NoTiling);
+    }
+    let rows = tiling.cells.chunks(mask.width.max(1) as usize);
+    let lines: Vec<String> = rows
+        .map(|row| {
+            row.iter()
+                .map(|cell| match cell {
+                    Cell::Horizontal => '-',
+                    Cell::Vertical => '|',
+                    _ => ' ',
+                })
+                .collect()
+        })
+        .collect();
+    Ok(lines.join("\n"))
+}
+
+/// Lay dominoes on the mask and deal them values from the set.
+pub fn generate_board(
+    mask: &Mask,
+    options: &Options,
+    set: DominoSet,
+    seed: Seed,
+) -> Result<Board, ProceduralError> {
+    let layout = generate_layout(mask, options, seed)?;
+    let mut board = Generator::build(&layout, set)
+        .map_err(ProceduralError::Layout)?
+        .board;
+    assign_values(&mut board, seed, set, &BTreeSet::new());
+    Ok(board)
+}
+
+/// Whether the mask has as many tiles of each colour of a chessboard, as every domino covers one
+/// of each.
+fn has_balanced_colors(mask: &Mask) -> bool {
+    let mut balance = 0i64;
+    for y in 0..mask.height {
+        for x in 0..mask.width {
+            if mask.contains(Position { x, y }) {
+                balance += if (x + y) % 2 == 0 { 1 } else { -1 };
+            }
+        }
+    }
+    balance == 0
+}
+
+/// True with the given probability, from the raw output of `rng`.
+fn chance(rng: &mut impl RngCore, probability: f64) -> bool {
+    ((rng.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < probability
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::game::board::generator::{generate_from_string_with_seed, validate_layout};
+    use crate::game::board::Tile;
+    use crate::game::domino::Orientation;
+
+    fn covered(board: &Board) -> usize {
+        board.dominoes().len() * 2
+    }
+
+    #[test]
+    fn full_tilings() {
+        let masks = [
+            Mask::rectangle(7, 4),
+            Mask::ring(8, 6, 2),
+            Mask::diamond(6, 6),
+            Mask::parse("##  ##\n######\n  ##"),
+        ];
+        for mask in &masks {
+            for seed in 0..10 {
+                let layout = generate_layout(mask, &Options::default(), seed).unwrap();
+                assert_eq!(validate_layout(&layout), []);
+                let board = generate_from_string_with_seed(&layout, seed).unwrap();
+                assert_eq!(covered(&board), mask.tile_count());
+                for y in 0..mask.height() {
+                    for x in 0..mask.width() {
+                        let position = Position { x, y };
+                        let tile = board.tile(position);
+                        assert_eq!(tile != Tile::Empty, mask.contains(position));
+                    }
+                }
+            }
+        }
+        assert_eq!(Mask::diamond(6, 6).tile_count(), 24);
+    }
+
+    #[test]
+    fn boards_and_options() {
+        let mask = Mask::rectangle(8, 6);
+        let options = Options {
+            horizontal_bias: 1.0,
+            ..Options::default()
+        };
+        let board = generate_board(&mask, &options, DominoSet::DOUBLE_SIX, 3).unwrap();
+        assert!(board
+            .dominoes()
+            .values()
+            .all(|domino| domino.orientation == Orientation::Horizontal));
+        assert_eq!(
+            generate_board(&mask, &options, DominoSet::DOUBLE_SIX, 3)
+                .unwrap()
+                .dominoes(),
+            board.dominoes()
+        );
+        let holes = Options {
+            density: 0.6,
+            ..Options::default()
+        };
+        let sparse = generate_board(&mask, &holes, DominoSet::DOUBLE_SIX, 3).unwrap();
+        assert!(covered(&sparse) < mask.tile_count());
+        assert!(covered(&sparse) > mask.tile_count() / 3);
+    }
+
+    #[test]
+    fn impossible_tilings() {
+        let options = Options::default();
+        for mask in [Mask::rectangle(3, 3), Mask::parse("#  \n###\n#")] {
+            assert!(matches!(
+                generate_layout(&mask, &options, 0),
+                Err(ProceduralError::NoTiling)
+            ));
+        }
+        assert!(matches!(
+            generate_board(&Mask::rectangle(10, 6), &options, DominoSet::DOUBLE_SIX, 0),
+            Err(ProceduralError::Layout(_))
+        ));
+        let holes = Options {
+            density: 0.9,
+            ..options
+        };
+        assert!(generate_layout(&Mask::rectangle(3, 3), &holes, 0).is_ok());
+    }
+
+    #[test]
+    fn symmetric_masks() {
+        let mask = Mask::random_symmetric(7, 6, 0.7, Symmetry::Both, 5);
+        for y in 0..6 {
+            for x in 0..7 {
+                let position = Position { x, y };
+                let mirrored = Position { x: 6 - x, y: 5 - y };
+                assert_eq!(mask.contains(position), mask.contains(mirrored));
+            }
+        }
+        assert_ne!(mask, Mask::random_symmetric(7, 6, 0.7, Symmetry::Both, 6));
+    }
+}
@@ -5,12 +5,14 @@ pub type Id = u32;
 pub type Value = u8;
 
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Orientation {
     Horizontal,
     Vertical,
 }
 
 #[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Values {
     pub head: Value,
     pub tail: Value,
@@ -38,6 +40,7 @@ impl From<(Value, Value)> for Values {
 }
 
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Domino {
     pub values: Values,
     pub position: Position,
@@ -0,0 +1,51 @@
+//! What happens during a hit, in the order frontends should show it.
+
+use crate::game::board::Position;
+use crate::game::domino::Id;
+use crate::game::Score;
+
+/// Logical time of an event: the step of the chain reaction it belongs to, the hit being step 0.
+pub type Time = usize;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Event {
+    pub time: Time,
+    pub kind: EventKind,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum EventKind {
+    /// The player hit the domino, removing it from the board.
+    Hit {
+        id: Id,
+    },
+    /// A half of the domino exploded at `origin`, blasting `tiles`.
+    Exploded {
+        id: Id,
+        origin: Position,
+        tiles: Vec<Position>,
+    },
+    /// The domino was blown up by the explosion of `by`.
+    Destroyed {
+        id: Id,
+        by: Id,
+    },
+    /// The score reached `score` after the dominoes removed in this step.
+    ScoreChanged {
+        score: Score,
+        change: Score,
+    },
+    GameFinished {
+        score: Score,
+    },
+}
+
+/// Number of steps of the chain reaction which had explosions, as in "Chain x3!".
+pub fn chain_length(events: &[Event]) -> usize {
+    events
+        .iter()
+        .filter(|event| matches!(event.kind, EventKind::Exploded { .. }))
+        .map(|event| event.time + 1)
+        .max()
+        .unwrap_or(0)
+}
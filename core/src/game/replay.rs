@@ -0,0 +1,306 @@
+//! Replays: the level, the seed it was dealt with and the dominoes hit, with the time of each hit.
+//!
+//! Playing the hits again must give the recorded score, which makes replays regression tests of
+//! the scoring rules as well as a way to watch a game again.
+
+use std::fmt;
+use std::io;
+
+use crate::game::board::generator::level::{Level, LevelError};
+use crate::game::board::generator::Seed;
+use crate::game::domino::{self, Domino};
+use crate::game::events::Event;
+use crate::game::{Game, Score};
+
+/// Version of the replay format.
+pub const VERSION: u32 = 1;
+
+/// Milliseconds since the start of the game.
+pub type Millis = u64;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hit {
+    pub id: domino::Id,
+    pub time: Millis,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Replay {
+    pub version: u32,
+    /// The level, in the level file format.
+    pub level: String,
+    pub seed: Option<Seed>,
+    pub hits: Vec<Hit>,
+    pub score: Score,
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(io::Error),
+    #[cfg(feature = "serde")]
+    Json(serde_json::Error),
+    UnsupportedVersion(u32),
+    Level(LevelError),
+    /// A hit of a domino which was not on the board. Steps are counted from 0.
+    NoDomino {
+        step: usize,
+        id: domino::Id,
+    },
+    ScoreMismatch {
+        recorded: Score,
+        replayed: Score,
+    },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "{}", error),
+            #[cfg(feature = "serde")]
+            Self::Json(error) => write!(f, "invalid replay: {}", error),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "recorded in version {} of the format, only version {} is supported",
+                version, VERSION
+            ),
+            Self::Level(error) => write!(f, "invalid level: {}", error),
+            Self::NoDomino { step, id } => {
+                write!(f, "hit {} is of domino {}, not on the board", step + 1, id)
+            }
+            Self::ScoreMismatch { recorded, replayed } => write!(
+                f,
+                "the hits score {} instead of the recorded {}",
+                replayed, recorded
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl Replay {
+    /// Record the moves of `game`, started from `level`, hit at the given times.
+    pub fn of_game(level: &Level, game: &Game, times: &[Millis]) -> Self {
+        let hits = game
+            .hits()
+            .zip(times.iter().copied().chain(std::iter::repeat(0)))
+            .map(|(id, time)| Hit { id, time })
+            .collect();
+        Self {
+            version: VERSION,
+            level: level.to_string(),
+            seed: game.seed(),
+            hits,
+            score: game.score(),
+        }
+    }
+
+    /// The game before the first hit.
+    pub fn start(&self) -> Result<Game, ReplayError> {
+        let level = Level::parse(&self.level).map_err(ReplayError::Level)?;
+        Ok(match self.seed {
+            Some(seed) => Game::new_from_level_seeded(&level, seed),
+            None => Game::new_from_level(&level),
+        })
+    }
+
+    /// Play all hits, checking that they give the recorded score.
+    pub fn verify(&self) -> Result<Game, ReplayError> {
+        let mut game = self.start()?;
+        for (step, hit) in self.hits.iter().enumerate() {
+            if !game.dominoes().contains_key(&hit.id) {
+                return Err(ReplayError::NoDomino { step, id: hit.id });
+            }
+            game.hit_domino(hit.id);
+        }
+        if game.score() != self.score {
+            return Err(ReplayError::ScoreMismatch {
+                recorded: self.score,
+                replayed: game.score(),
+            });
+        }
+        Ok(game)
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Replay is serializable")
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> Result<Self, ReplayError> {
+        #[derive(serde::Deserialize)]
+        struct Header {
+            version: u32,
+        }
+        let header: Header = serde_json::from_str(json).map_err(ReplayError::Json)?;
+        if header.version != VERSION {
+            return Err(ReplayError::UnsupportedVersion(header.version));
+        }
+        serde_json::from_str(json).map_err(ReplayError::Json)
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn save(&self, path: &std::path::Path) -> Result<(), ReplayError> {
+        if let Some(directory) = path.parent() {
+            std::fs::create_dir_all(directory).map_err(ReplayError::Io)?;
+        }
+        std::fs::write(path, self.to_json()).map_err(ReplayError::Io)
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn load(path: &std::path::Path) -> Result<Self, ReplayError> {
+        Self::from_json(&std::fs::read_to_string(path).map_err(ReplayError::Io)?)
+    }
+}
+
+/// Plays a replay on the game returned by [`Replay::start`], following the recorded times or
+/// step by step.
+#[derive(Clone, Debug)]
+pub struct Player {
+    replay: Replay,
+    step: usize,
+    clock: Millis,
+}
+
+impl Player {
+    pub fn new(replay: Replay) -> Result<(Self, Game), ReplayError> {
+        let game = replay.start()?;
+        let player = Self {
+            replay,
+            step: 0,
+            clock: 0,
+        };
+        Ok((player, game))
+    }
+
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    /// The number of hits played.
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    pub fn is_at_end(&self) -> bool {
+        self.step == self.replay.hits.len()
+    }
+
+    /// Play the next hit, returning its events, or `None` at the end of the replay or if the
+    /// domino is not on the board.
+    pub fn step_forward(&mut self, game: &mut Game) -> Option<Vec<Event>> {
+        let hit = *self.replay.hits.get(self.step)?;
+        if !game.dominoes().contains_key(&hit.id) {
+            return None;
+        }
+        self.step += 1;
+        self.clock = self.clock.max(hit.time);
+        Some(game.hit_domino_events(hit.id))
+    }
+
+    /// Undo the last hit, returning the dominoes put back.
+    pub fn step_back(&mut self, game: &mut Game) -> Option<Vec<(domino::Id, Domino)>> {
+        if self.step == 0 {
+            return None;
+        }
+        let restored = game.undo()?;
+        self.step -= 1;
+        self.clock = match self.step {
+            0 => 0,
+            step => self.replay.hits[step - 1].time,
+        };
+        Some(restored)
+    }
+
+    /// Move the clock forward, playing the hits it reaches. Returns the events of each hit.
+    pub fn advance(&mut self, game: &mut Game, elapsed: Millis) -> Vec<Vec<Event>> {
+        self.clock += elapsed;
+        let mut played = Vec::new();
+        while self
+            .replay
+            .hits
+            .get(self.step)
+            .is_some_and(|hit| hit.time <= self.clock)
+        {
+            match self.step_forward(game) {
+                Some(events) => played.push(events),
+                None => break,
+            }
+        }
+        played
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const LEVEL: &str = "name: Test\n[layout]\n--|--\n  |--\n[values]\n10123\n  245";
+
+    fn recorded() -> (Game, Replay) {
+        let level = Level::parse(LEVEL).unwrap();
+        let mut game = Game::new_from_level(&level);
+        game.hit_domino(1);
+        game.hit_domino(2);
+        game.undo();
+        game.hit_domino(3);
+        let replay = Replay::of_game(&level, &game, &[100, 250]);
+        (game, replay)
+    }
+
+    #[test]
+    fn recording_and_verifying() {
+        let (game, replay) = recorded();
+        assert_eq!(
+            replay.hits,
+            [Hit { id: 1, time: 100 }, Hit { id: 3, time: 250 }]
+        );
+        assert_eq!(replay.score, game.score());
+        assert_eq!(replay.verify().unwrap().board(), game.board());
+
+        let mut wrong = replay.clone();
+        wrong.score += 1;
+        assert!(matches!(
+            wrong.verify(),
+            Err(ReplayError::ScoreMismatch { recorded, replayed })
+                if recorded == game.score() + 1 && replayed == game.score()
+        ));
+        wrong.hits.push(Hit { id: 1, time: 300 });
+        assert!(matches!(
+            wrong.verify(),
+            Err(ReplayError::NoDomino { step: 2, id: 1 })
+        ));
+    }
+
+    #[test]
+    fn playing() {
+        let (recorded, replay) = recorded();
+        let (mut player, mut game) = Player::new(replay).unwrap();
+        assert!(player.advance(&mut game, 99).is_empty());
+        assert_eq!(player.advance(&mut game, 1).len(), 1);
+        assert!(player.step_back(&mut game).is_some());
+        assert!(player.step_back(&mut game).is_none());
+        assert_eq!(player.step(), 0);
+        assert!(player.step_forward(&mut game).is_some());
+        assert_eq!(player.advance(&mut game, 1000).len(), 1);
+        assert!(player.is_at_end());
+        assert!(player.step_forward(&mut game).is_none());
+        assert_eq!(game.board(), recorded.board());
+        assert_eq!(game.score(), recorded.score());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn saving() {
+        let (_, replay) = recorded();
+        assert_eq!(Replay::from_json(&replay.to_json()).unwrap(), replay);
+        let other_version = replay.to_json().replace("\"version\": 1", "\"version\": 2");
+        assert!(matches!(
+            Replay::from_json(&other_version),
+            Err(ReplayError::UnsupportedVersion(2))
+        ));
+    }
+}
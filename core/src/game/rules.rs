@@ -0,0 +1,168 @@
+//! How hits are scored.
+
+use std::collections::BTreeMap;
+
+use crate::game::board::{Board, Coord, Position};
+use crate::game::{domino, Score, EXPLOSIVE_VALUE};
+
+/// Tiles blasted by an explosion, around the exploding half of a domino.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ExplosionShape {
+    /// The four orthogonal neighbours.
+    Cross,
+    /// All eight neighbours.
+    Ring,
+    /// Tiles at most this many orthogonal steps away.
+    Diamond(Coord),
+    /// The whole row and column.
+    Line,
+}
+
+impl ExplosionShape {
+    /// Positions blasted by an explosion at `origin`, clipped to the `board`.
+    pub fn blasted_positions(self, board: &Board, origin: Position) -> Vec<Position> {
+        let offsets: Vec<(i64, i64)> = match self {
+            Self::Cross => vec![(0, -1), (1, 0), (0, 1), (-1, 0)],
+            Self::Ring => vec![
+                (0, -1),
+                (1, -1),
+                (1, 0),
+                (1, 1),
+                (0, 1),
+                (-1, 1),
+                (-1, 0),
+                (-1, -1),
+            ],
+            Self::Diamond(radius) => {
+                let radius = radius as i64;
+                (-radius..=radius)
+                    .flat_map(|dy| {
+                        let reach = radius - dy.abs();
+                        (-reach..=reach).map(move |dx| (dx, dy))
+                    })
+                    .filter(|offset| *offset != (0, 0))
+                    .collect()
+            }
+            Self::Line => {
+                let (x, y) = (origin.x as i64, origin.y as i64);
+                let row = (0..board.width() as i64).map(|column| (column - x, 0));
+                let column = (0..board.height() as i64).map(|row| (0, row - y));
+                row.chain(column)
+                    .filter(|offset| *offset != (0, 0))
+                    .collect()
+            }
+        };
+        offsets
+            .into_iter()
+            .filter_map(|(dx, dy)| {
+                let x = Coord::try_from(origin.x as i64 + dx).ok()?;
+                let y = Coord::try_from(origin.y as i64 + dy).ok()?;
+                (x < board.width() && y < board.height()).then_some(Position { x, y })
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rules {
+    /// Values which make a domino explode when it is removed.
+    pub explosive_values: Vec<domino::Value>,
+    /// Whether hitting a double which does not explode costs its value.
+    pub doubles_penalise: bool,
+    /// How many times its value a domino scores when it explodes or is destroyed by an explosion.
+    pub explosion_multiplier: Score,
+    /// Percentage of its score added to each domino for every domino removed before it by the same
+    /// hit: with 50, the second domino removed scores 150%, the third 200% and so on.
+    pub combo_percent: Score,
+    /// Multipliers for dominoes blown up at each depth of a chain reaction, starting with the hit
+    /// domino. The last one applies to all deeper dominoes; with none, depth does not matter.
+    pub depth_multipliers: Vec<Score>,
+    /// Shape of explosions of values without one in `value_explosion_shapes`.
+    pub explosion_shape: ExplosionShape,
+    /// Shapes of explosions of particular values, overriding `explosion_shape`.
+    pub value_explosion_shapes: BTreeMap<domino::Value, ExplosionShape>,
+    /// Shape of explosions of doubles, overriding the shape of their value.
+    pub double_explosion_shape: Option<ExplosionShape>,
+}
+
+impl Rules {
+    /// The rules the game was designed with: blanks explode into their eight neighbours, doubles
+    /// penalise, and every domino blown up scores its value, however long the chain.
+    pub fn classic() -> Self {
+        Self {
+            explosive_values: vec![EXPLOSIVE_VALUE],
+            doubles_penalise: true,
+            explosion_multiplier: 1,
+            combo_percent: 0,
+            depth_multipliers: Vec::new(),
+            explosion_shape: ExplosionShape::Ring,
+            value_explosion_shapes: BTreeMap::new(),
+            double_explosion_shape: None,
+        }
+    }
+
+    pub fn is_explosive(&self, value: domino::Value) -> bool {
+        self.explosive_values.contains(&value)
+    }
+
+    /// Shape of the explosion of the `value` half of a domino with `values`.
+    pub fn explosion_shape(&self, values: domino::Values, value: domino::Value) -> ExplosionShape {
+        let double_shape = self
+            .double_explosion_shape
+            .filter(|_| values.head == values.tail);
+        double_shape
+            .or_else(|| self.value_explosion_shapes.get(&value).copied())
+            .unwrap_or(self.explosion_shape)
+    }
+
+    /// Score of a domino with `values` removed by a hit, after `removed_before` other dominoes and
+    /// at `depth` of the chain reaction.
+    pub fn score(
+        &self,
+        values: domino::Values,
+        blown_up: bool,
+        removed_before: usize,
+        depth: usize,
+    ) -> Score {
+        let value = (values.head + values.tail) as Score;
+        if blown_up {
+            let score = value * self.explosion_multiplier * self.depth_multiplier(depth);
+            score + score * self.combo_percent * removed_before as Score / 100
+        } else if self.doubles_penalise && values.head == values.tail {
+            -value
+        } else {
+            0
+        }
+    }
+
+    /// A bound on what removing all of `dominoes`, in any number of hits, can score. Signs are
+    /// ignored, as a negative multiplier and a negative combo percent together make points.
+    pub fn max_score(&self, dominoes: &[domino::Values]) -> Score {
+        let value = dominoes
+            .iter()
+            .map(|values| (values.head + values.tail) as Score)
+            .sum::<Score>();
+        let multiplier = self
+            .depth_multipliers
+            .iter()
+            .map(|multiplier| (multiplier * self.explosion_multiplier).abs())
+            .max()
+            .unwrap_or(self.explosion_multiplier.abs());
+        let total = value * multiplier;
+        let combo = self.combo_percent.abs() * dominoes.len().saturating_sub(1) as Score;
+        total + total * combo / 100
+    }
+
+    fn depth_multiplier(&self, depth: usize) -> Score {
+        let last = self.depth_multipliers.len().checked_sub(1);
+        last.map_or(1, |last| self.depth_multipliers[depth.min(last)])
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self::classic()
+    }
+}
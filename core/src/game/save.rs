@@ -0,0 +1,160 @@
+//! Saved games: a [`Game`] with its whole history, stored as JSON or in a compact binary form.
+//!
+//! Both forms start with the version of the format, so files written by other versions are
+//! rejected instead of misread.
+
+use std::path::Path;
+
+use crate::game::Game;
+
+/// Version of the format written by [`SavedGame::to_bytes`].
+pub const VERSION: u32 = 1;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SaveFormat {
+    Json,
+    Binary,
+}
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(std::io::Error),
+    UnsupportedVersion(u32),
+    Json(serde_json::Error),
+    Binary(bincode::Error),
+}
+
+impl std::fmt::Display for SaveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "{}", error),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "saved in version {} of the format, only version {} is supported",
+                version, VERSION
+            ),
+            Self::Json(error) => write!(f, "invalid saved game: {}", error),
+            Self::Binary(error) => write!(f, "invalid saved game: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+#[derive(Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SavedGame {
+    pub version: u32,
+    /// Identifies the level played, as chosen by the frontend which saved the game.
+    pub level: String,
+    pub game: Game,
+}
+
+#[derive(serde::Deserialize)]
+struct Header {
+    version: u32,
+}
+
+impl SavedGame {
+    pub fn new(level: String, game: Game) -> Self {
+        Self {
+            version: VERSION,
+            level,
+            game,
+        }
+    }
+
+    pub fn to_bytes(&self, format: SaveFormat) -> Vec<u8> {
+        match format {
+            SaveFormat::Json => serde_json::to_vec_pretty(self).expect("Game is serializable"),
+            SaveFormat::Binary => bincode::serialize(self).expect("Game is serializable"),
+        }
+    }
+
+    /// Read a game saved in either format.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SaveError> {
+        let is_json = bytes.iter().find(|byte| !byte.is_ascii_whitespace()) == Some(&b'{');
+        let version = if is_json {
+            serde_json::from_slice::<Header>(bytes)
+                .map_err(SaveError::Json)?
+                .version
+        } else {
+            bincode::deserialize::<u32>(bytes).map_err(SaveError::Binary)?
+        };
+        if version != VERSION {
+            return Err(SaveError::UnsupportedVersion(version));
+        }
+        if is_json {
+            serde_json::from_slice(bytes).map_err(SaveError::Json)
+        } else {
+            bincode::deserialize(bytes).map_err(SaveError::Binary)
+        }
+    }
+
+    pub fn save(&self, path: &Path, format: SaveFormat) -> Result<(), SaveError> {
+        if let Some(directory) = path.parent() {
+            std::fs::create_dir_all(directory).map_err(SaveError::Io)?;
+        }
+        std::fs::write(path, self.to_bytes(format)).map_err(SaveError::Io)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, SaveError> {
+        Self::from_bytes(&std::fs::read(path).map_err(SaveError::Io)?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::game::board::generator::level::Level;
+    use crate::test_dir::TestDir;
+
+    fn game_in_progress() -> Game {
+        let level = Level::parse("[layout]\n--|--\n  |--").unwrap();
+        let mut game = Game::new_from_level_seeded(&level, 3);
+        let ids: Vec<_> = game.dominoes().keys().copied().collect();
+        game.hit_domino(ids[0]);
+        game.hit_domino(ids[1]);
+        game.undo();
+        game
+    }
+
+    #[test]
+    fn saving_and_loading() {
+        let saved = SavedGame::new("level".to_owned(), game_in_progress());
+        for format in [SaveFormat::Json, SaveFormat::Binary] {
+            let bytes = saved.to_bytes(format);
+            let mut loaded = SavedGame::from_bytes(&bytes).unwrap();
+            assert_eq!(loaded, saved);
+            let mut game = saved.game.clone();
+            assert_eq!(loaded.game.redo(), game.redo());
+        }
+        let directory = TestDir::new();
+        let path = directory.join("game.json");
+        saved.save(&path, SaveFormat::Json).unwrap();
+        assert_eq!(SavedGame::load(&path).unwrap(), saved);
+    }
+
+    #[test]
+    fn rejecting_other_versions() {
+        let mut saved = SavedGame::new("level".to_owned(), game_in_progress());
+        saved.version = VERSION + 1;
+        for format in [SaveFormat::Json, SaveFormat::Binary] {
+            assert!(matches!(
+                SavedGame::from_bytes(&saved.to_bytes(format)),
+                Err(SaveError::UnsupportedVersion(version)) if version == VERSION + 1
+            ));
+        }
+        assert!(matches!(
+            SavedGame::from_bytes(b"{\"version\": 1}"),
+            Err(SaveError::Json(_))
+        ));
+        assert!(matches!(
+            SavedGame::from_bytes(&[1, 0, 0, 0, 7]),
+            Err(SaveError::Binary(_))
+        ));
+        assert!(matches!(
+            SavedGame::load(Path::new("/nonexistent/dombreaker/save.json")),
+            Err(SaveError::Io(_))
+        ));
+    }
+}
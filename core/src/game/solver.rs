@@ -0,0 +1,241 @@
+//! Finding the best final score a game can still reach.
+//!
+//! The search goes through orders of hits, using [`Game::hit_domino`] so chained explosions and
+//! penalties for doubles are scored exactly as in play. Two observations keep it small:
+//!
+//! * The outcome of the rest of the game depends only on which dominoes remain, so a set of
+//!   remaining dominoes reached again with a score no better than before is not searched twice.
+//! * While no rule makes explosions cost points, hitting a domino which does not explode never
+//!   helps: it neither blasts anything nor changes how explosions spread, and left on the board it
+//!   may still be blown up for points. Such dominoes are then hit only once no explosive domino
+//!   remains. Rules with negative multipliers or combo percent are searched through every hit.
+
+use std::collections::HashMap;
+
+use crate::game::rules::Rules;
+use crate::game::{domino, Game, Score};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Solution {
+    /// Score at the end of the game.
+    pub score: Score,
+    /// Hits leading to that score, clearing the whole board.
+    pub moves: Vec<domino::Id>,
+}
+
+pub fn solve(game: &Game) -> Solution {
+    let mut solver = Solver {
+        best: None,
+        visited: HashMap::new(),
+        explosives_first: explosions_never_cost(game.rules()),
+    };
+    solver.search(game.clone(), &mut Vec::new());
+    solver.best.unwrap()
+}
+
+struct Solver {
+    best: Option<Solution>,
+    visited: HashMap<Vec<domino::Id>, Score>,
+    /// Whether to hit only explosive dominoes while some remain.
+    explosives_first: bool,
+}
+
+fn explosions_never_cost(rules: &Rules) -> bool {
+    rules.explosion_multiplier >= 0
+        && rules.combo_percent >= 0
+        && rules
+            .depth_multipliers
+            .iter()
+            .all(|multiplier| *multiplier >= 0)
+}
+
+impl Solver {
+    fn search(&mut self, game: Game, moves: &mut Vec<domino::Id>) {
+        if self.upper_bound(&game) <= self.best.as_ref().map_or(Score::MIN, |best| best.score) {
+            return;
+        }
+        let remaining = game.dominoes().keys().copied().collect::<Vec<_>>();
+        match self.visited.get(&remaining) {
+            Some(score) if *score >= game.score() => return,
+            _ => {
+                self.visited.insert(remaining, game.score());
+            }
+        }
+
+        let explosive = game
+            .dominoes()
+            .iter()
+            .filter(|(_, domino)| game.is_explosive(domino))
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+        if explosive.is_empty() {
+            self.finish(game, moves);
+            return;
+        }
+        let hits = if self.explosives_first {
+            explosive
+        } else {
+            game.dominoes().keys().copied().collect()
+        };
+        for id in hits {
+            let mut next = game.clone();
+            next.hit_domino(id);
+            moves.push(id);
+            self.search(next, moves);
+            moves.pop();
+        }
+    }
+
+    fn finish(&mut self, mut game: Game, moves: &[domino::Id]) {
+        let mut moves = moves.to_vec();
+        while let Some(id) = game.dominoes().keys().next().copied() {
+            game.hit_domino(id);
+            moves.push(id);
+        }
+        if self
+            .best
+            .as_ref()
+            .is_none_or(|best| game.score() > best.score)
+        {
+            self.best = Some(Solution {
+                score: game.score(),
+                moves,
+            });
+        }
+    }
+
+    /// No domino can give more than it gets when blown up at the end of the longest chain.
+    fn upper_bound(&self, game: &Game) -> Score {
+        let values = game
+            .dominoes()
+            .values()
+            .map(|domino| domino.values)
+            .collect::<Vec<_>>();
+        game.rules().max_score(&values) + game.score()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::game::board::generator;
+    use crate::game::rules::{ExplosionShape, Rules};
+
+    fn brute_force(game: &Game) -> Score {
+        if game.is_finished() {
+            return game.score();
+        }
+        game.dominoes()
+            .keys()
+            .map(|id| {
+                let mut next = game.clone();
+                next.hit_domino(*id);
+                brute_force(&next)
+            })
+            .max()
+            .unwrap()
+    }
+
+    fn replay(game: &Game, moves: &[domino::Id]) -> Game {
+        let mut game = game.clone();
+        for id in moves {
+            game.hit_domino(*id);
+        }
+        game
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let layout = "--|\n\
+                      --|\n\
+                      |--\n\
+                      |--";
+        for seed in 0..20 {
+            let game = Game::new_generated_seeded(layout, seed).unwrap();
+            let solution = solve(&game);
+            assert_eq!(solution.score, brute_force(&game), "seed {}", seed);
+            let replayed = replay(&game, &solution.moves);
+            assert!(replayed.is_finished());
+            assert_eq!(replayed.score(), solution.score);
+        }
+    }
+
+    #[test]
+    fn matches_brute_force_with_other_rules() {
+        let layout = "--|\n\
+                      --|\n\
+                      -- ";
+        let rules = Rules {
+            explosive_values: vec![0, 5],
+            doubles_penalise: false,
+            explosion_multiplier: 3,
+            combo_percent: 25,
+            depth_multipliers: vec![1, 2, 3],
+            explosion_shape: ExplosionShape::Cross,
+            ..Rules::classic()
+        };
+        for seed in 0..10 {
+            let board = generator::generate_from_string_with_seed(layout, seed).unwrap();
+            let game = Game::new(board, rules.clone());
+            assert_eq!(solve(&game).score, brute_force(&game), "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn matches_brute_force_with_costly_explosions() {
+        let layout = "|--|\n\
+                      |--|\n\
+                      ----";
+        let costly_rules = [
+            Rules {
+                explosion_multiplier: -1,
+                ..Rules::classic()
+            },
+            Rules {
+                combo_percent: -150,
+                ..Rules::classic()
+            },
+            Rules {
+                explosion_multiplier: -1,
+                combo_percent: -150,
+                ..Rules::classic()
+            },
+            Rules {
+                depth_multipliers: vec![1, -2],
+                ..Rules::classic()
+            },
+        ];
+        for rules in costly_rules {
+            for seed in 0..50 {
+                let board = generator::generate_from_string_with_seed(layout, seed).unwrap();
+                let game = Game::new(board, rules.clone());
+                let solution = solve(&game);
+                assert_eq!(
+                    solution.score,
+                    brute_force(&game),
+                    "{:?} seed {}",
+                    rules,
+                    seed
+                );
+                assert_eq!(replay(&game, &solution.moves).score(), solution.score);
+            }
+        }
+    }
+
+    #[test]
+    fn solving_full_set() {
+        let layout = "--------\n\
+                      |------|\n\
+                      ||----||\n\
+                      |||--|||\n\
+                      |||--|||\n\
+                      ||----||\n\
+                      |------|";
+        let game = Game::new_generated_seeded(layout, 7).unwrap();
+        let solution = solve(&game);
+        let replayed = replay(&game, &solution.moves);
+        assert!(replayed.is_finished());
+        assert_eq!(replayed.score(), solution.score);
+        assert!(solution.score >= 0);
+    }
+}
@@ -0,0 +1,11 @@
+//! The Domino Breaker engine: boards and levels, the rules of hitting dominoes, and a solver,
+//! without any user interface.
+
+pub mod game;
+#[cfg(test)]
+mod test_dir;
+
+pub use game::board::generator::level::{Level, LevelPack};
+pub use game::board::Board;
+pub use game::rules::Rules;
+pub use game::{DominoRemoved, Game, Score};
@@ -15,11 +15,18 @@ Rectangle {
         id: brokenDominoComponent
         Domino {
             id: brokenDomino
-            PropertyAnimation on opacity {
-                from: 1.0
-                to: 0.0
-                duration: 1500.0
-                easing.type: Easing.InSine
+            property int delay: 0
+
+            SequentialAnimation on opacity {
+                PauseAnimation {
+                    duration: brokenDomino.delay
+                }
+                NumberAnimation {
+                    from: 1.0
+                    to: 0.0
+                    duration: 1500.0
+                    easing.type: Easing.InSine
+                }
 
                 onFinished: brokenDomino.destroy()
             }
@@ -33,6 +40,7 @@ Rectangle {
         DominoBackground {
             horizontal: domino.horizontal
             boardPosition: domino.board_position
+            highlighted: domino.game_id === game.hinted_domino
 
             onClicked: {
                 game.domino_hit(domino.game_id)
@@ -41,12 +49,13 @@ Rectangle {
             Connections {
                 target: domino
 
-                function onBroken() {
+                function onBroken(time) {
                     let properties = {
                         horizontal: domino.horizontal,
                         boardPosition: domino.board_position,
                         headValue: domino.head_value,
                         tailValue: domino.tail_value,
+                        delay: time * 200,
                     }
                     if (brokenDominoComponent.createObject(board, properties) === null) {
                         console.error("ERROR while creating broken Domino")
@@ -4,12 +4,13 @@ import QtQuick.Layouts 1.2
 Rectangle {
     property point boardPosition
     property bool horizontal: false
+    property bool highlighted: false
 
     signal clicked();
 
     x: boardPosition.x * board.tileSize
     y: boardPosition.y * board.tileSize
-    border.color: "green";
+    border.color: highlighted ? "yellow" : "green";
     border.width: 4;
     color: "red";
     width: board.tileSize;
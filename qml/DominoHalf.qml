@@ -6,8 +6,18 @@ Item {
     property int value;
     height: width
 
+    // There is no room for more than nine pips, larger values are written as numbers.
+    Text {
+        anchors.centerIn: parent
+        visible: value > 9
+        text: value
+        font.pixelSize: dominoHalf.width / 2
+        font.bold: true
+    }
+
     Grid {
         columns: 3
+        visible: value <= 9
 
         anchors.fill: parent
         columnSpacing: width / 8
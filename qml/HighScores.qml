@@ -0,0 +1,69 @@
+import QtQuick 2.12
+import QtQuick.Controls 2.15
+import Dombreaker.Models 1.0
+
+Rectangle {
+    property GameModel game
+
+    signal playAgain()
+
+    width: content.width + 32
+    height: content.height + 32
+    color: "white"
+    border.color: "darkred"
+    border.width: 2
+    radius: 8
+
+    Column {
+        id: content
+        anchors.centerIn: parent
+        spacing: 8
+
+        Text {
+            text: "Final score: " + game.score
+            font.bold: true
+        }
+
+        TextEdit {
+            text: game.daily_summary
+            visible: game.daily_summary !== ""
+            readOnly: true
+            selectByMouse: true
+        }
+
+        Row {
+            spacing: 8
+            visible: !game.score_saved
+
+            TextField {
+                text: game.player_name
+                placeholderText: "Your name"
+                onTextEdited: game.player_name = text
+            }
+
+            Button {
+                text: "Save score"
+                onClicked: game.save_score()
+            }
+        }
+
+        Text {
+            text: "High scores"
+            font.bold: true
+        }
+
+        Repeater {
+            model: game.high_scores
+
+            Text {
+                text: (index + 1) + ". " + player + ": " + score
+                      + " (" + moves + " moves, " + date + ")"
+            }
+        }
+
+        Button {
+            text: "Try again!"
+            onClicked: playAgain()
+        }
+    }
+}
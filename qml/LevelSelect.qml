@@ -0,0 +1,42 @@
+import QtQuick 2.12
+import QtQuick.Controls 2.15
+import Dombreaker.Models 1.0
+
+Rectangle {
+    property GameModel game
+
+    width: content.width + 32
+    height: content.height + 32
+    color: "white"
+    border.color: "darkred"
+    border.width: 2
+    radius: 8
+
+    Column {
+        id: content
+        anchors.centerIn: parent
+        spacing: 8
+
+        Text {
+            text: "Choose a level"
+            font.bold: true
+        }
+
+        Repeater {
+            model: game.levels
+
+            Button {
+                text: (index + 1) + ". " + name
+                      + " (" + difficulty + ")"
+                      + (locked ? " - locked" : has_best_score ? " - best: " + best_score : "")
+                enabled: !locked
+                onClicked: game.select_level(index)
+            }
+        }
+
+        Button {
+            text: "Daily puzzle"
+            onClicked: game.play_daily()
+        }
+    }
+}
@@ -4,15 +4,135 @@ import QtQuick.Controls 2.15
 import Dombreaker.Models 1.0
 
 Window {
-    width: board.width
-    height: board.height + topBar.height
+    width: Math.max(board.width, topBar.width)
+    height: board.height + topBar.height + (replayBar.visible ? replayBar.height : 0)
     visible: true
     title: "Domino Breaker"
 
     Column {
-        Text {
+        Row {
             id: topBar
-            text: "Score: " + game.score;
+            spacing: 8
+
+            Text {
+                anchors.verticalCenter: parent.verticalCenter
+                text: game.level_name
+            }
+
+            Text {
+                anchors.verticalCenter: parent.verticalCenter
+                text: "(" + game.difficulty + ")"
+                visible: game.difficulty !== ""
+            }
+
+            Text {
+                anchors.verticalCenter: parent.verticalCenter
+                text: "Score: " + game.score;
+            }
+
+            Text {
+                anchors.verticalCenter: parent.verticalCenter
+                text: "Seed: " + game.seed;
+            }
+
+            Text {
+                anchors.verticalCenter: parent.verticalCenter
+                text: "Chain x" + game.chain + "!"
+                color: "darkred"
+                font.bold: true
+                visible: game.chain >= 2
+            }
+
+            Button {
+                text: "Levels"
+                onClicked: game.show_levels()
+            }
+
+            Button {
+                text: "Undo"
+                enabled: game.can_undo
+                onClicked: game.undo()
+            }
+
+            Button {
+                text: "Redo"
+                enabled: game.can_redo
+                onClicked: game.redo()
+            }
+
+            Button {
+                text: "Hint"
+                enabled: !game.finished
+                onClicked: game.show_hint()
+            }
+
+            Button {
+                text: "Save"
+                enabled: !game.finished
+                onClicked: game.save_game()
+            }
+
+            Button {
+                text: "Load"
+                onClicked: game.load_game()
+            }
+
+            Button {
+                text: "Save replay"
+                enabled: !game.replaying
+                onClicked: game.save_replay()
+            }
+
+            Button {
+                text: "Watch replay"
+                onClicked: game.watch_replay()
+            }
+        }
+
+        Row {
+            id: replayBar
+            spacing: 8
+            visible: game.replaying
+
+            Text {
+                anchors.verticalCenter: parent.verticalCenter
+                text: "Replay " + game.replay_step + "/" + game.replay_steps
+            }
+
+            Button {
+                text: "<"
+                onClicked: game.replay_step_back()
+            }
+
+            Button {
+                text: game.replay_paused ? "Play" : "Pause"
+                onClicked: game.toggle_replay_pause()
+            }
+
+            Button {
+                text: ">"
+                onClicked: game.replay_step_forward()
+            }
+
+            Button {
+                text: "Slower"
+                onClicked: game.replay_slower()
+            }
+
+            Text {
+                anchors.verticalCenter: parent.verticalCenter
+                text: "Speed: " + game.replay_speed + "%"
+            }
+
+            Button {
+                text: "Faster"
+                onClicked: game.replay_faster()
+            }
+
+            Button {
+                text: "Stop"
+                onClicked: game.stop_replay()
+            }
         }
 
         Board {
@@ -21,31 +141,37 @@ Window {
         }
     }
 
-    Button {
+    Text {
         anchors.centerIn: parent
-        text: "Try again!"
-        visible: game.finished
-
-        onClicked: recreate_game()
+        text: "Invalid level: " + game.error
+        color: "red"
+        visible: game.error !== ""
     }
 
+    HighScores {
+        anchors.centerIn: parent
+        game: game
+        visible: game.finished && game.error === "" && !game.selecting_level && !game.replaying
 
-    property string level: "--------
-|------|
-||----||
-|||--|||
-|||--|||
-||----||
-|------|"
+        onPlayAgain: game.restart()
+    }
+
+    LevelSelect {
+        anchors.centerIn: parent
+        game: game
+        visible: game.selecting_level
+    }
 
     GameModel {
         id: game
     }
 
-    function recreate_game() {
-        game.new_game(level)
+    Timer {
+        interval: 50
+        repeat: true
+        running: game.replaying && !game.replay_paused
+        onTriggered: game.tick(interval)
     }
 
-    Component.onCompleted: recreate_game()
-    onLevelChanged: recreate_game()
+    Component.onCompleted: game.start()
 }
@@ -0,0 +1,109 @@
+//! The daily puzzle: a layout and deal drawn from the date on the local clock, so everyone playing
+//! on the same day gets the same board.
+
+use chrono::{Datelike, NaiveDate};
+use dombreaker_core::game::board::generator::constrained::{self, Constraint};
+use dombreaker_core::game::board::generator::procedural::{self, Mask, Options, Symmetry};
+use dombreaker_core::game::board::generator::Seed;
+use dombreaker_core::game::board::Coord;
+use dombreaker_core::{Game, Level, Rules, Score};
+
+const WIDTH: Coord = 8;
+const HEIGHT: Coord = 6;
+const LAYOUT_OPTIONS: Options = Options {
+    density: 0.9,
+    horizontal_bias: 0.5,
+};
+/// Deals tried to keep explosive dominoes apart, before settling for the first one.
+const MAX_DEAL_ATTEMPTS: usize = 100;
+
+pub fn today() -> NaiveDate {
+    chrono::Local::now().date_naive()
+}
+
+/// The date as a number, such as 20211231.
+pub fn seed(date: NaiveDate) -> Seed {
+    date.year() as Seed * 10000 + date.month() as Seed * 100 + date.day() as Seed
+}
+
+pub fn level(date: NaiveDate) -> Level {
+    let seed = seed(date);
+    let mask = match seed % 4 {
+        0 => Mask::rectangle(WIDTH, HEIGHT),
+        1 => Mask::ring(WIDTH, HEIGHT, 2),
+        2 => Mask::diamond(WIDTH, WIDTH),
+        _ => {
+            let symmetries = [Symmetry::LeftRight, Symmetry::TopBottom, Symmetry::Both];
+            let symmetry = symmetries[(seed / 4 % 3) as usize];
+            Mask::random_symmetric(WIDTH, HEIGHT, 0.8, symmetry, seed)
+        }
+    };
+    let layout = procedural::generate_layout(&mask, &LAYOUT_OPTIONS, seed)
+        .ok()
+        .filter(|layout| layout.contains(['-', '|']))
+        // A full rectangle of even width always has a tiling.
+        .unwrap_or_else(|| {
+            let rectangle = Mask::rectangle(WIDTH, HEIGHT);
+            procedural::generate_layout(&rectangle, &Options::default(), seed).unwrap()
+        });
+    let source = |seed| format!("name: Daily {}\nseed: {}\n[layout]\n{}", date, seed, layout);
+    let level = Level::parse(&source(seed)).expect("Invalid daily level");
+    let constraints = [Constraint::NoAdjacentExplosives];
+    match constrained::find_seed(
+        &level,
+        &Rules::classic(),
+        &constraints,
+        MAX_DEAL_ATTEMPTS,
+        seed,
+    ) {
+        Ok(found) => Level::parse(&source(found)).expect("Invalid daily level"),
+        Err(_) => level,
+    }
+}
+
+/// The line to share once the daily puzzle is finished.
+pub fn summary(date: NaiveDate, game: &Game, optimal_score: Score) -> String {
+    format!(
+        "Domino Breaker daily {}: score {} (optimal {}), moves {}",
+        date,
+        game.score(),
+        optimal_score,
+        game.moves_made()
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn levels_of_days() {
+        let date = NaiveDate::from_ymd_opt(2021, 12, 31).unwrap();
+        assert_eq!(seed(date), 20211231);
+        let level = level(date);
+        assert_eq!(level.info.name, "Daily 2021-12-31");
+        assert_eq!(level.to_string(), super::level(date).to_string());
+        let game = Game::new_from_level(&level);
+        assert_eq!(
+            game.board().dominoes(),
+            Game::new_from_level(&level).board().dominoes()
+        );
+
+        for day in 1..=8 {
+            let level = super::level(NaiveDate::from_ymd_opt(2022, 1, day).unwrap());
+            assert!(!level.layout.is_empty());
+            assert_ne!(level.to_string(), super::level(date).to_string());
+        }
+    }
+
+    #[test]
+    fn summaries() {
+        let level = Level::parse("[layout]\n--|\n  |\n[values]\n101\n  2").unwrap();
+        let mut game = Game::new_from_level(&level);
+        game.hit_domino(0);
+        assert_eq!(
+            summary(NaiveDate::from_ymd_opt(2021, 5, 1).unwrap(), &game, 4),
+            "Domino Breaker daily 2021-05-01: score 4 (optimal 4), moves 1"
+        );
+    }
+}
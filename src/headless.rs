@@ -0,0 +1,482 @@
+//! `dombreaker play`: playing a level without any user interface, for scripts and bots.
+//!
+//! Moves are domino ids or `x:y` board coordinates, given with `--moves` separated by commas or
+//! read from the standard input, one or more per line. Every hit is reported as it is played, as
+//! text or as JSON objects, one per line. The game can be recorded as a replay.
+//!
+//! With `--constraint`, the level is dealt from the first seed, starting from the given one, whose
+//! deal meets the constraints. With `--daily`, today's daily puzzle is played instead of a level
+//! file, and the line to share is printed at the end.
+//!
+//! `dombreaker replay` checks that replays still give their recorded scores.
+
+use crate::daily;
+use dombreaker_core::game::board::generator::constrained::{self, Constraint};
+use dombreaker_core::game::board::generator::Seed;
+use dombreaker_core::game::board::{self, Board, Position};
+use dombreaker_core::game::domino;
+use dombreaker_core::game::replay::{Millis, Replay};
+use dombreaker_core::game::solver;
+use dombreaker_core::{DominoRemoved, Game, Level};
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+const USAGE: &str = "Usage: dombreaker play --level FILE|--daily [--seed N] [--moves M,M,...] \
+    [--format text|json] [--record REPLAY] [--constraint C]... [--attempts N]\n\
+    Moves are domino ids or x:y board coordinates. Without --moves, they are read from the \
+    standard input.\n\
+    Constraints are min-optimal-score=N, no-adjacent-explosives and min-penalised-doubles=N.";
+const REPLAY_USAGE: &str = "Usage: dombreaker replay REPLAY...";
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Format {
+    Text,
+    Json,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Move {
+    Domino(domino::Id),
+    Tile(Position),
+}
+
+impl std::str::FromStr for Move {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid move \"{}\"", s);
+        match s.split_once(':') {
+            Some((x, y)) => {
+                let x = x.trim().parse().map_err(|_| invalid())?;
+                let y = y.trim().parse().map_err(|_| invalid())?;
+                Ok(Self::Tile(Position { x, y }))
+            }
+            None => s.trim().parse().map(Self::Domino).map_err(|_| invalid()),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct Options {
+    level: Option<PathBuf>,
+    daily: bool,
+    seed: Option<Seed>,
+    moves: Option<Vec<Move>>,
+    format: Format,
+    record: Option<PathBuf>,
+    constraints: Vec<Constraint>,
+    /// Deals tried to meet the constraints.
+    attempts: usize,
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut level = None;
+        let mut daily = false;
+        let mut seed = None;
+        let mut moves = None;
+        let mut format = Format::Text;
+        let mut record = None;
+        let mut constraints = Vec::new();
+        let mut attempts = constrained::DEFAULT_MAX_ATTEMPTS;
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("Missing value of {}", arg));
+            match arg.as_str() {
+                "--level" => level = Some(PathBuf::from(value()?)),
+                "--daily" => daily = true,
+                "--seed" => {
+                    let value = value()?;
+                    let parsed = value.parse();
+                    seed = Some(parsed.map_err(|_| format!("Invalid seed \"{}\"", value))?);
+                }
+                "--moves" => moves = Some(parse_moves(&value()?)?),
+                "--format" => {
+                    format = match value()?.as_str() {
+                        "text" => Format::Text,
+                        "json" => Format::Json,
+                        other => return Err(format!("Unknown format \"{}\"", other)),
+                    }
+                }
+                "--record" => record = Some(PathBuf::from(value()?)),
+                "--constraint" => constraints.push(value()?.parse()?),
+                "--attempts" => {
+                    let value = value()?;
+                    let parsed = value.parse();
+                    attempts = parsed.map_err(|_| format!("Invalid attempts \"{}\"", value))?;
+                }
+                other => return Err(format!("Unexpected argument \"{}\"", other)),
+            }
+        }
+        if daily && (level.is_some() || seed.is_some() || !constraints.is_empty()) {
+            return Err("The daily puzzle has its own level and deal".to_owned());
+        }
+        if !daily && level.is_none() {
+            return Err("Missing --level or --daily".to_owned());
+        }
+        Ok(Self {
+            level,
+            daily,
+            seed,
+            moves,
+            format,
+            record,
+            constraints,
+            attempts,
+        })
+    }
+}
+
+fn parse_moves(list: &str) -> Result<Vec<Move>, String> {
+    list.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|s| !s.is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+/// Run `dombreaker play` with the arguments after `play`, returning the exit code.
+pub fn main(args: impl Iterator<Item = String>) -> i32 {
+    let options = match Options::parse(args) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{}\n{}", error, USAGE);
+            return 2;
+        }
+    };
+    let date = daily::today();
+    let (level, path) = match &options.level {
+        Some(path) => match Level::load(path) {
+            Ok(level) => (level, path.display().to_string()),
+            Err(error) => {
+                eprintln!("Cannot load {}: {}", path.display(), error);
+                return 1;
+            }
+        },
+        None => (daily::level(date), "the daily puzzle".to_owned()),
+    };
+    let mut game = match options.seed {
+        Some(seed) => Game::new_from_level_seeded(&level, seed),
+        None => Game::new_from_level(&level),
+    };
+    if !options.constraints.is_empty() {
+        let start = game.seed().unwrap_or_default();
+        let found = constrained::find_seed(
+            &level,
+            game.rules(),
+            &options.constraints,
+            options.attempts,
+            start,
+        );
+        match found {
+            Ok(seed) => game = Game::new_from_level_seeded(&level, seed),
+            Err(error) => {
+                eprintln!("Cannot deal {}: {}", path, error);
+                return 1;
+            }
+        }
+    }
+    let mut session = Session {
+        format: options.format,
+        game: &mut game,
+        started: Instant::now(),
+        times: Vec::new(),
+    };
+    session.print_start(&level);
+    let code = match options.moves {
+        Some(moves) => match moves.into_iter().try_for_each(|hit| session.play(hit)) {
+            Ok(()) => 0,
+            Err(error) => {
+                eprintln!("{}", error);
+                1
+            }
+        },
+        None => {
+            for line in std::io::stdin().lock().lines().map_while(Result::ok) {
+                let played = parse_moves(&line)
+                    .and_then(|moves| moves.into_iter().try_for_each(|hit| session.play(hit)));
+                if let Err(error) = played {
+                    eprintln!("{}", error);
+                }
+            }
+            0
+        }
+    };
+    session.print_end();
+    if options.daily && session.game.is_finished() {
+        let optimal = solver::solve(&Game::new_from_level(&level)).score;
+        let summary = daily::summary(date, session.game, optimal);
+        match options.format {
+            Format::Text => println!("{}", summary),
+            Format::Json => println!("{}", serde_json::json!({ "share": summary })),
+        }
+    }
+    if let Some(path) = options.record {
+        let replay = Replay::of_game(&level, session.game, &session.times);
+        if let Err(error) = replay.save(&path) {
+            eprintln!("Cannot record {}: {}", path.display(), error);
+            return 1;
+        }
+    }
+    code
+}
+
+/// Run `dombreaker replay` with the paths of the replays to check, returning the exit code.
+pub fn verify(paths: impl Iterator<Item = String>) -> i32 {
+    let mut any_checked = false;
+    let mut any_failed = false;
+    for path in paths {
+        any_checked = true;
+        match Replay::load(Path::new(&path)).and_then(|replay| replay.verify()) {
+            Ok(game) => println!("{}: score {}", path, game.score()),
+            Err(error) => {
+                println!("{}: {}", path, error);
+                any_failed = true;
+            }
+        }
+    }
+    if !any_checked {
+        eprintln!("{}", REPLAY_USAGE);
+        2
+    } else if any_failed {
+        1
+    } else {
+        0
+    }
+}
+
+struct Session<'a> {
+    format: Format,
+    game: &'a mut Game,
+    started: Instant,
+    /// When each move was played.
+    times: Vec<Millis>,
+}
+
+impl Session<'_> {
+    fn play(&mut self, hit: Move) -> Result<(), String> {
+        let id = match hit {
+            Move::Domino(id) if self.game.dominoes().contains_key(&id) => id,
+            Move::Tile(position) if self.game.board().contains(position) => {
+                match self.game.board().tile(position) {
+                    board::Tile::Head(id) | board::Tile::Tail(id) => id,
+                    board::Tile::Empty => return Err(format!("No domino at {}", position)),
+                }
+            }
+            Move::Domino(id) => return Err(format!("No domino {} on the board", id)),
+            Move::Tile(position) => return Err(format!("Tile {} is off the board", position)),
+        };
+        let removed = self.game.hit_domino(id);
+        self.times
+            .push(self.started.elapsed().as_millis() as Millis);
+        self.print_hit(id, &removed);
+        Ok(())
+    }
+
+    fn print_start(&self, level: &Level) {
+        let board = self.game.board();
+        match self.format {
+            Format::Text => {
+                println!("Level: {}", level.info.name);
+                if let Some(seed) = self.game.seed() {
+                    println!("Seed: {}", seed);
+                }
+                print!("{}", board_text(board));
+            }
+            Format::Json => {
+                let dominoes: Vec<_> = board
+                    .dominoes()
+                    .iter()
+                    .map(|(id, domino)| {
+                        serde_json::json!({
+                            "id": id,
+                            "x": domino.position.x,
+                            "y": domino.position.y,
+                            "horizontal": domino.orientation == domino::Orientation::Horizontal,
+                            "head": domino.values.head,
+                            "tail": domino.values.tail,
+                        })
+                    })
+                    .collect();
+                let start = serde_json::json!({
+                    "level": level.info.name,
+                    "seed": self.game.seed(),
+                    "width": board.width(),
+                    "height": board.height(),
+                    "dominoes": dominoes,
+                });
+                println!("{}", start);
+            }
+        }
+    }
+
+    fn print_hit(&self, id: domino::Id, removed: &[DominoRemoved]) {
+        match self.format {
+            Format::Text => {
+                println!("Hit {}:", id);
+                for removed in removed {
+                    let how = match removed.parent {
+                        Some(parent) => format!("destroyed by {}", parent),
+                        None => "hit".to_owned(),
+                    };
+                    let exploded = if removed.exploded { ", exploded" } else { "" };
+                    println!(
+                        "  {} {}{}, scored {}",
+                        removed.id, how, exploded, removed.score_awarded
+                    );
+                }
+                println!("Score: {}", self.game.score());
+            }
+            Format::Json => {
+                let hit = serde_json::json!({
+                    "hit": id,
+                    "removed": removed,
+                    "score": self.game.score(),
+                });
+                println!("{}", hit);
+            }
+        }
+    }
+
+    fn print_end(&self) {
+        match self.format {
+            Format::Text => {
+                print!("{}", board_text(self.game.board()));
+                println!("Final score: {}", self.game.score());
+            }
+            Format::Json => {
+                let end = serde_json::json!({
+                    "score": self.game.score(),
+                    "moves": self.game.moves_made(),
+                    "finished": self.game.is_finished(),
+                });
+                println!("{}", end);
+            }
+        }
+    }
+}
+
+/// The values of the dominoes on the board as hex digits, '.' marking empty tiles.
+fn board_text(board: &Board) -> String {
+    let mut text = String::new();
+    for y in 0..board.height() {
+        for x in 0..board.width() {
+            let value = match board.tile(Position { x, y }) {
+                board::Tile::Empty => None,
+                board::Tile::Head(id) => Some(board.dominoes()[&id].values.head),
+                board::Tile::Tail(id) => Some(board.dominoes()[&id].values.tail),
+            };
+            let digit = value.and_then(|value| std::char::from_digit(value as u32, 16));
+            text.push(digit.unwrap_or('.'));
+        }
+        text.push('\n');
+    }
+    text
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_dir::TestDir;
+
+    fn args(args: &str) -> impl Iterator<Item = String> + '_ {
+        args.split_whitespace().map(str::to_owned)
+    }
+
+    #[test]
+    fn parsing_options() {
+        assert_eq!(
+            Options::parse(args(
+                "--level a.level --seed 5 --moves 3,7,2:4 --format json --record a.replay \
+                 --constraint no-adjacent-explosives --constraint min-optimal-score=40 \
+                 --attempts 20"
+            )),
+            Ok(Options {
+                level: Some("a.level".into()),
+                daily: false,
+                seed: Some(5),
+                moves: Some(vec![
+                    Move::Domino(3),
+                    Move::Domino(7),
+                    Move::Tile((2, 4).into())
+                ]),
+                format: Format::Json,
+                record: Some("a.replay".into()),
+                constraints: vec![
+                    Constraint::NoAdjacentExplosives,
+                    Constraint::MinOptimalScore(40)
+                ],
+                attempts: 20,
+            })
+        );
+        assert_eq!(
+            Options::parse(args("--level a.level")).map(|options| options.moves),
+            Ok(None)
+        );
+        assert!(Options::parse(args("--seed 5")).is_err());
+        assert_eq!(
+            Options::parse(args("--daily")).map(|options| (options.level, options.daily)),
+            Ok((None, true))
+        );
+        assert!(Options::parse(args("--daily --seed 5")).is_err());
+        assert!(Options::parse(args("--level a.level --moves 3,x")).is_err());
+        assert!(Options::parse(args("--level a.level --format xml")).is_err());
+        assert!(Options::parse(args("--level")).is_err());
+        assert!(Options::parse(args("--level a.level --constraint most-fun")).is_err());
+        assert!(Options::parse(args("--level a.level --attempts many")).is_err());
+    }
+
+    /// Every replay in the `replays` directory must still give its recorded score.
+    #[test]
+    fn recorded_replays() {
+        let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("replays");
+        let mut count = 0;
+        for entry in std::fs::read_dir(directory).unwrap() {
+            let path = entry.unwrap().path();
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "replay")
+            {
+                let replay = Replay::load(&path).unwrap();
+                if let Err(error) = replay.verify() {
+                    panic!("{}: {}", path.display(), error);
+                }
+                count += 1;
+            }
+        }
+        assert!(count > 0);
+    }
+
+    #[test]
+    fn verifying_replays() {
+        let replays = Path::new(env!("CARGO_MANIFEST_DIR")).join("replays");
+        let good = replays.join("stripes.replay").display().to_string();
+        let directory = TestDir::new();
+        let bad = directory.join("bad.replay");
+        std::fs::write(&bad, "not a replay").unwrap();
+        let bad = bad.display().to_string();
+        assert_eq!(verify([good.clone()].into_iter()), 0);
+        assert_eq!(verify([bad.clone(), good.clone()].into_iter()), 1);
+        assert_eq!(verify([good, bad.clone()].into_iter()), 1);
+        assert_eq!(verify(std::iter::empty()), 2);
+    }
+
+    #[test]
+    fn playing() {
+        let level = Level::parse("[layout]\n--|\n  |\n[values]\n101\n  2").unwrap();
+        let mut game = Game::new_from_level(&level);
+        assert_eq!(board_text(game.board()), "101\n..2\n");
+        let mut session = Session {
+            format: Format::Json,
+            game: &mut game,
+            started: Instant::now(),
+            times: Vec::new(),
+        };
+        assert!(session.play(Move::Tile((0, 1).into())).is_err());
+        assert!(session.play(Move::Tile((3, 0).into())).is_err());
+        assert!(session.play(Move::Domino(2)).is_err());
+        assert_eq!(session.play(Move::Tile((1, 0).into())), Ok(()));
+        assert_eq!(session.times.len(), 1);
+        assert!(game.is_finished());
+        assert_eq!(game.score(), 4);
+    }
+}
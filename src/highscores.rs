@@ -0,0 +1,296 @@
+//! Best scores of each level, kept between runs in the user's data directory. Daily puzzles have a
+//! table of their own.
+//!
+//! The file has one tab-separated line per entry: level key, score, date, number of moves and
+//! player name.
+
+use crate::log::warn;
+use dombreaker_core::game::board::generator::level::Level;
+use dombreaker_core::game::board::generator::{DominoSet, Seed};
+use dombreaker_core::game::Score;
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+pub const MAX_ENTRIES_PER_LEVEL: usize = 10;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const FILE: &str = "highscores.tsv";
+const DAILY_FILE: &str = "daily.tsv";
+
+/// Identifies a level: the layout, and the seed if the level always deals the same board.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct LevelKey {
+    pub layout_hash: u64,
+    pub seed: Option<Seed>,
+}
+
+impl LevelKey {
+    pub fn new(layout: &str, seed: Option<Seed>) -> Self {
+        // FNV-1a, as the key must stay the same across releases and platforms.
+        let layout_hash = layout.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        });
+        Self { layout_hash, seed }
+    }
+
+    pub fn of_level(level: &Level) -> Self {
+        let mut description = level.layout.clone();
+        if level.info.set != DominoSet::default() {
+            description += &format!("\n[set]\n{}", level.info.set);
+        }
+        if let Some(values) = &level.values {
+            description += &format!("\n[values]\n{}", values);
+        }
+        Self::new(
+            &description,
+            level.info.seed.filter(|_| level.deals_values()),
+        )
+    }
+}
+
+impl fmt::Display for LevelKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.layout_hash)?;
+        if let Some(seed) = self.seed {
+            write!(f, "-{}", seed)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for LevelKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (hash, seed) = match s.split_once('-') {
+            Some((hash, seed)) => (hash, Some(seed)),
+            None => (s, None),
+        };
+        let layout_hash = u64::from_str_radix(hash, 16).map_err(|e| e.to_string())?;
+        let seed = seed
+            .map(|seed| seed.parse().map_err(|_| format!("Invalid seed {}", seed)))
+            .transpose()?;
+        Ok(Self { layout_hash, seed })
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Entry {
+    pub score: Score,
+    pub date: chrono::NaiveDate,
+    pub moves: usize,
+    pub player: String,
+}
+
+impl Entry {
+    pub fn new_today(score: Score, moves: usize, player: &str) -> Self {
+        Self {
+            score,
+            date: chrono::Local::now().date_naive(),
+            moves,
+            player: player.replace(|c: char| c.is_control(), " "),
+        }
+    }
+
+    fn ranks_before(&self, other: &Entry) -> bool {
+        (-self.score, self.moves, self.date) < (-other.score, other.moves, other.date)
+    }
+}
+
+/// The name to offer when asking a player for theirs.
+pub fn default_player_name() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_default()
+}
+
+pub struct HighScores {
+    path: Option<PathBuf>,
+    entries: HashMap<LevelKey, Vec<Entry>>,
+}
+
+impl HighScores {
+    fn path_in_data_dir(file: &str) -> Option<PathBuf> {
+        Some(dirs::data_dir()?.join("dombreaker").join(file))
+    }
+
+    /// Load scores from the default file. Problems are logged, and never stop the game.
+    pub fn load() -> Self {
+        Self::load_in_data_dir(FILE)
+    }
+
+    /// Load the scores of daily puzzles, like [`HighScores::load`].
+    pub fn load_daily() -> Self {
+        Self::load_in_data_dir(DAILY_FILE)
+    }
+
+    fn load_in_data_dir(file: &str) -> Self {
+        let path = Self::path_in_data_dir(file);
+        if path.is_none() {
+            warn!("No data directory found, high scores will not be saved");
+        }
+        match path.as_deref().map(Self::load_from) {
+            Some(Ok(high_scores)) => high_scores,
+            Some(Err(error)) => {
+                warn!("Cannot read high scores: {}", error);
+                Self::empty(path)
+            }
+            None => Self::empty(None),
+        }
+    }
+
+    pub fn load_from(path: &Path) -> io::Result<Self> {
+        let mut high_scores = Self::empty(Some(path.to_owned()));
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(error) => return Err(error),
+        };
+        for (number, line) in content.lines().enumerate() {
+            match Self::parse_line(line) {
+                Ok((key, entry)) => {
+                    high_scores.insert(key, entry);
+                }
+                Err(error) => warn!("Skipping high score line {}: {}", number + 1, error),
+            }
+        }
+        Ok(high_scores)
+    }
+
+    pub fn scores(&self, key: &LevelKey) -> &[Entry] {
+        self.entries.get(key).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn best(&self, key: &LevelKey) -> Option<&Entry> {
+        self.scores(key).first()
+    }
+
+    /// Add the entry and save the table, returning the entry's rank if it made it to the table.
+    pub fn record(&mut self, key: LevelKey, entry: Entry) -> io::Result<Option<usize>> {
+        let rank = self.insert(key, entry);
+        self.save()?;
+        Ok(rank)
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        if let Some(directory) = path.parent() {
+            std::fs::create_dir_all(directory)?;
+        }
+        let mut content = String::new();
+        for (key, entries) in &self.entries {
+            for entry in entries {
+                content += &format!(
+                    "{}\t{}\t{}\t{}\t{}\n",
+                    key,
+                    entry.score,
+                    entry.date.format(DATE_FORMAT),
+                    entry.moves,
+                    entry.player
+                );
+            }
+        }
+        std::fs::write(path, content)
+    }
+
+    fn empty(path: Option<PathBuf>) -> Self {
+        Self {
+            path,
+            entries: HashMap::new(),
+        }
+    }
+
+    fn insert(&mut self, key: LevelKey, entry: Entry) -> Option<usize> {
+        let entries = self.entries.entry(key).or_default();
+        let rank = entries
+            .iter()
+            .position(|other| entry.ranks_before(other))
+            .unwrap_or(entries.len());
+        entries.insert(rank, entry);
+        entries.truncate(MAX_ENTRIES_PER_LEVEL);
+        Some(rank).filter(|rank| *rank < MAX_ENTRIES_PER_LEVEL)
+    }
+
+    fn parse_line(line: &str) -> Result<(LevelKey, Entry), String> {
+        let fields: Vec<&str> = line.splitn(5, '\t').collect();
+        if let [key, score, date, moves, player] = fields[..] {
+            let entry = Entry {
+                score: score
+                    .parse()
+                    .map_err(|_| format!("Invalid score {}", score))?,
+                date: chrono::NaiveDate::parse_from_str(date, DATE_FORMAT)
+                    .map_err(|_| format!("Invalid date {}", date))?,
+                moves: moves
+                    .parse()
+                    .map_err(|_| format!("Invalid moves {}", moves))?,
+                player: player.to_owned(),
+            };
+            Ok((key.parse()?, entry))
+        } else {
+            Err(format!("Expected 5 fields, got {}", fields.len()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_dir::TestDir;
+
+    fn entry(score: Score, moves: usize, player: &str) -> Entry {
+        Entry {
+            score,
+            date: chrono::NaiveDate::from_ymd_opt(2021, 5, 1).unwrap(),
+            moves,
+            player: player.to_owned(),
+        }
+    }
+
+    #[test]
+    fn level_keys() {
+        let key = LevelKey::new("--\n--", None);
+        assert_eq!(key, LevelKey::new("--\n--", None));
+        assert_ne!(key, LevelKey::new("|\n|", None));
+        assert_eq!(key.to_string().parse(), Ok(key));
+        let seeded = LevelKey::new("--\n--", Some(42));
+        assert_eq!(seeded.to_string().parse(), Ok(seeded));
+        assert_ne!(key, seeded);
+    }
+
+    #[test]
+    fn ranking() {
+        let mut high_scores = HighScores::empty(None);
+        let key = LevelKey::new("--", None);
+        assert_eq!(high_scores.record(key, entry(10, 5, "b")).unwrap(), Some(0));
+        assert_eq!(high_scores.record(key, entry(10, 3, "a")).unwrap(), Some(0));
+        assert_eq!(high_scores.record(key, entry(-4, 3, "c")).unwrap(), Some(2));
+        for _ in 0..MAX_ENTRIES_PER_LEVEL {
+            high_scores.record(key, entry(20, 1, "d")).unwrap();
+        }
+        assert_eq!(high_scores.record(key, entry(1, 1, "e")).unwrap(), None);
+        assert_eq!(high_scores.scores(&key).len(), MAX_ENTRIES_PER_LEVEL);
+        assert_eq!(high_scores.best(&key).unwrap().score, 20);
+        assert!(high_scores.best(&LevelKey::new("|\n|", None)).is_none());
+    }
+
+    #[test]
+    fn saving_and_loading() {
+        let directory = TestDir::new();
+        let path = directory.join("highscores.tsv");
+        let mut high_scores = HighScores::load_from(&path).unwrap();
+        let key = LevelKey::new("--", Some(7));
+        high_scores
+            .record(key, entry(12, 4, "Some Player"))
+            .unwrap();
+        high_scores.record(key, entry(3, 2, "Other")).unwrap();
+
+        let loaded = HighScores::load_from(&path).unwrap();
+        assert_eq!(loaded.scores(&key), high_scores.scores(&key));
+    }
+}
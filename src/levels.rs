@@ -0,0 +1,79 @@
+//! The level pack played in the frontends, and the progress made in it.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::highscores::{HighScores, LevelKey};
+use dombreaker_core::game::analysis;
+use dombreaker_core::game::board::generator::level::{Difficulty, Level, LevelError, LevelPack};
+use dombreaker_core::game::board::generator::Seed;
+use dombreaker_core::game::{Game, Score};
+
+const BUNDLED_PACK: &str = include_str!("../levels/classic.pack");
+
+/// Load the pack given as the first command-line argument, or the bundled one.
+pub fn load_pack() -> Result<LevelPack, LevelError> {
+    match std::env::args_os().nth(1) {
+        Some(path) => LevelPack::load(Path::new(&path)),
+        None => LevelPack::parse("classic", BUNDLED_PACK),
+    }
+}
+
+/// A level as listed on the level-select screen.
+#[derive(Clone, Debug)]
+pub struct LevelSummary {
+    pub name: String,
+    pub difficulty: Difficulty,
+    pub best_score: Option<Score>,
+    pub locked: bool,
+}
+
+/// A level counts as completed once it has an entry in the high-score table.
+pub fn summaries(
+    pack: &LevelPack,
+    high_scores: &HighScores,
+    difficulties: &mut Difficulties,
+) -> Vec<LevelSummary> {
+    let unlocked =
+        pack.unlocked_levels(|level| high_scores.best(&LevelKey::of_level(level)).is_some());
+    pack.levels
+        .iter()
+        .enumerate()
+        .map(|(index, level)| LevelSummary {
+            name: level.info.name.clone(),
+            difficulty: difficulties.of(level, level.info.seed.unwrap_or_default()),
+            best_score: high_scores
+                .best(&LevelKey::of_level(level))
+                .map(|entry| entry.score),
+            locked: index >= unlocked,
+        })
+        .collect()
+}
+
+/// Difficulties rated so far, as rating a deal runs the solver and many playouts.
+#[derive(Default)]
+pub struct Difficulties(HashMap<(LevelKey, Seed), Difficulty>);
+
+impl Difficulties {
+    /// The difficulty declared by the level, or else the one rated for the deal of the given seed.
+    pub fn of(&mut self, level: &Level, seed: Seed) -> Difficulty {
+        level.info.difficulty.unwrap_or_else(|| {
+            *self
+                .0
+                .entry((LevelKey::of_level(level), seed))
+                .or_insert_with(|| {
+                    analysis::analyse(&Game::new_from_level_seeded(level, seed)).difficulty()
+                })
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn bundled_pack_is_valid() {
+        LevelPack::parse("classic", BUNDLED_PACK).unwrap();
+    }
+}
@@ -1,23 +1,54 @@
-mod game;
+// Tests build the modules shared by the frontends without any frontend using all of them.
+#![cfg_attr(
+    all(
+        test,
+        not(any(feature = "qml_ui", feature = "sixtyfps_ui", feature = "tui_ui"))
+    ),
+    allow(dead_code)
+)]
+
+mod daily;
+mod headless;
+#[cfg(any(feature = "qml_ui", feature = "sixtyfps_ui", feature = "tui_ui", test))]
+mod highscores;
+#[cfg(any(feature = "qml_ui", feature = "sixtyfps_ui", feature = "tui_ui", test))]
+mod levels;
+#[cfg(any(feature = "qml_ui", feature = "sixtyfps_ui", feature = "tui_ui", test))]
+mod presenter;
+#[cfg(any(feature = "qml_ui", feature = "sixtyfps_ui", feature = "tui_ui", test))]
+mod saves;
+#[cfg(test)]
+mod test_dir;
 
 #[cfg(feature = "qml_ui")]
 mod qt_ui;
 #[cfg(feature = "sixtyfps_ui")]
 mod sixty_ui;
+#[cfg(feature = "tui_ui")]
+mod tui_ui;
 
 pub use log;
 
 fn main() {
     simple_logger::SimpleLogger::new().init().unwrap();
 
+    match std::env::args().nth(1).as_deref() {
+        Some("play") => std::process::exit(headless::main(std::env::args().skip(2))),
+        Some("replay") => std::process::exit(headless::verify(std::env::args().skip(2))),
+        _ => {}
+    }
+
     cfg_if::cfg_if! {
         if #[cfg(feature = "sixtyfps_ui")] {
             sixty_ui::main();
         } else if #[cfg(feature = "qml_ui")] {
             qt_ui::main();
+        } else if #[cfg(feature = "tui_ui")] {
+            tui_ui::main();
         } else {
-            compile_error!("No UI framework enabled. Please run cargo with `--features sixtyfps` or \
-            `--features qt` option");
+            eprintln!("No UI framework enabled. Please run cargo with `--features sixtyfps_ui`, \
+            `--features qml_ui` or `--features tui_ui` option, or use `dombreaker play`");
+            std::process::exit(2);
         }
     }
 }
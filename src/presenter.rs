@@ -0,0 +1,906 @@
+//! Frontend-independent side of the UIs: the level pack, the game being played, its saves and its
+//! high scores, shown through a [`View`] implemented by each UI backend.
+
+use crate::daily;
+use crate::highscores::{self, HighScores, LevelKey};
+use crate::levels::{self, Difficulties, LevelSummary};
+use crate::log::{info, warn};
+use crate::saves::Saves;
+use dombreaker_core::game::board::generator::level::{Difficulty, Level, LevelError, LevelPack};
+use dombreaker_core::game::board::generator::Seed;
+use dombreaker_core::game::board::Coord;
+use dombreaker_core::game::domino::{self, Domino};
+use dombreaker_core::game::events::{self, Event, EventKind, Time};
+use dombreaker_core::game::replay::{Millis, Player, Replay};
+use dombreaker_core::game::save::SavedGame;
+use dombreaker_core::game::{analysis, Game, Score};
+use std::time::{Duration, Instant};
+
+/// Speeds of replays, as percents of the recorded speed.
+pub const REPLAY_SPEEDS: [u32; 5] = [25, 50, 100, 200, 400];
+const NORMAL_SPEED: usize = 2;
+
+/// Everything about the game shown outside of the board, compared with what was shown before to
+/// update the view only on changes.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct GameView {
+    pub level_name: String,
+    pub board_width: Coord,
+    pub board_height: Coord,
+    pub seed: Option<Seed>,
+    pub difficulty: Option<Difficulty>,
+    pub score: Score,
+    /// Steps of the last chain reaction, as in "Chain x3!".
+    pub chain: usize,
+    pub finished: bool,
+    pub can_undo: bool,
+    pub can_redo: bool,
+    pub hinted_domino: Option<domino::Id>,
+    pub error: Option<String>,
+    /// Set while watching a replay, when the player cannot hit dominoes.
+    pub replay: Option<ReplayView>,
+    /// The line to share once the daily puzzle is finished.
+    pub daily_summary: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct ReplayView {
+    /// The number of hits played, out of `steps`.
+    pub step: usize,
+    pub steps: usize,
+    pub paused: bool,
+    /// Percent of the recorded speed.
+    pub speed: u32,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct DominoView {
+    pub id: domino::Id,
+    pub x: Coord,
+    pub y: Coord,
+    pub head_value: domino::Value,
+    pub tail_value: domino::Value,
+    pub horizontal: bool,
+}
+
+impl DominoView {
+    pub fn new(id: domino::Id, domino: &Domino) -> Self {
+        Self {
+            id,
+            x: domino.position.x,
+            y: domino.position.y,
+            head_value: domino.values.head,
+            tail_value: domino.values.tail,
+            horizontal: domino.orientation == domino::Orientation::Horizontal,
+        }
+    }
+}
+
+pub trait View {
+    fn show_game(&mut self, game: &GameView);
+    /// Replace all dominoes on the board, as when a level starts.
+    fn reset_dominoes(&mut self, dominoes: &[DominoView]);
+    /// Remove the dominoes, each at the step of the chain reaction which removed it.
+    fn break_dominoes(&mut self, broken: &[(domino::Id, Time)]);
+    fn restore_dominoes(&mut self, dominoes: &[DominoView]);
+    fn show_high_scores(&mut self, entries: &[highscores::Entry], saved: bool);
+    fn show_levels(&mut self, levels: &[LevelSummary]);
+    fn hide_levels(&mut self);
+    fn player_name(&self) -> String;
+}
+
+pub struct Presenter {
+    pack: Option<LevelPack>,
+    current_level: usize,
+    game: Option<Game>,
+    high_scores: HighScores,
+    daily_scores: HighScores,
+    /// Set while playing the daily puzzle instead of a level of the pack.
+    daily: Option<Daily>,
+    /// The date of the daily puzzle to play.
+    today: fn() -> chrono::NaiveDate,
+    saves: Saves,
+    score_saved: bool,
+    chain: usize,
+    hinted_domino: Option<domino::Id>,
+    difficulty: Option<Difficulty>,
+    difficulties: Difficulties,
+    error: Option<String>,
+    shown: Option<GameView>,
+    started: Instant,
+    /// When each move of the game was played, since `started`.
+    hit_times: Vec<Millis>,
+    playback: Option<Playback>,
+}
+
+struct Playback {
+    player: Player,
+    level_name: String,
+    paused: bool,
+    /// Index in [`REPLAY_SPEEDS`].
+    speed: usize,
+}
+
+struct Daily {
+    date: chrono::NaiveDate,
+    level: Level,
+    optimal_score: Score,
+    difficulty: Difficulty,
+    summary: Option<String>,
+}
+
+impl Daily {
+    /// Analyse the board once, as the daily level declares no difficulty.
+    fn new(date: chrono::NaiveDate, level: Level) -> Self {
+        let analysis = analysis::analyse(&Game::new_from_level(&level));
+        Self {
+            date,
+            level,
+            optimal_score: analysis.optimal_score,
+            difficulty: analysis.difficulty(),
+            summary: None,
+        }
+    }
+}
+
+impl Playback {
+    fn view(&self) -> ReplayView {
+        ReplayView {
+            step: self.player.step(),
+            steps: self.player.replay().hits.len(),
+            paused: self.paused,
+            speed: REPLAY_SPEEDS[self.speed],
+        }
+    }
+}
+
+impl Presenter {
+    pub fn new(
+        pack: Result<LevelPack, LevelError>,
+        high_scores: HighScores,
+        daily_scores: HighScores,
+        saves: Saves,
+    ) -> Self {
+        let (pack, error) = match pack {
+            Ok(pack) => (Some(pack), None),
+            Err(error) => {
+                warn!("Cannot load levels: {}", error);
+                (None, Some(error.to_string()))
+            }
+        };
+        Self {
+            pack,
+            current_level: 0,
+            game: None,
+            high_scores,
+            daily_scores,
+            daily: None,
+            today: daily::today,
+            saves,
+            score_saved: false,
+            chain: 0,
+            hinted_domino: None,
+            difficulty: None,
+            difficulties: Difficulties::default(),
+            error,
+            shown: None,
+            started: Instant::now(),
+            hit_times: Vec::new(),
+            playback: None,
+        }
+    }
+
+    pub fn view_model(&self) -> GameView {
+        let game = match &self.game {
+            Some(game) => game,
+            None => {
+                return GameView {
+                    error: self.error.clone(),
+                    ..GameView::default()
+                }
+            }
+        };
+        let level_name = match &self.playback {
+            Some(playback) => playback.level_name.clone(),
+            None => self.level().expect("Game without levels").info.name.clone(),
+        };
+        GameView {
+            level_name,
+            board_width: game.board().width(),
+            board_height: game.board().height(),
+            seed: game.seed(),
+            difficulty: self.difficulty,
+            score: game.score(),
+            chain: self.chain,
+            finished: game.is_finished(),
+            can_undo: game.can_undo(),
+            can_redo: game.can_redo(),
+            hinted_domino: self.hinted_domino,
+            error: None,
+            replay: self.playback.as_ref().map(Playback::view),
+            daily_summary: self
+                .daily
+                .as_ref()
+                .and_then(|daily| daily.summary.clone())
+                .filter(|_| game.is_finished() && self.playback.is_none()),
+        }
+    }
+
+    /// The level being played: the daily puzzle, or a level of the pack.
+    fn level(&self) -> Option<&Level> {
+        match &self.daily {
+            Some(daily) => Some(&daily.level),
+            None => Some(&self.pack.as_ref()?.levels[self.current_level]),
+        }
+    }
+
+    fn level_high_scores(&mut self) -> &mut HighScores {
+        match self.daily {
+            Some(_) => &mut self.daily_scores,
+            None => &mut self.high_scores,
+        }
+    }
+
+    /// Resume the game left unfinished on the last run, or let the player select a level.
+    pub fn start(&mut self, view: &mut impl View) {
+        let resumed = match self.saves.load_autosave() {
+            Ok(Some(saved)) => !saved.game.is_finished() && self.resume(view, saved),
+            Ok(None) => false,
+            Err(error) => {
+                warn!("Cannot resume the last game: {}", error);
+                false
+            }
+        };
+        if !resumed {
+            self.show_levels(view);
+        }
+    }
+
+    pub fn show_levels(&mut self, view: &mut impl View) {
+        match &self.pack {
+            Some(pack) => view.show_levels(&levels::summaries(
+                pack,
+                &self.high_scores,
+                &mut self.difficulties,
+            )),
+            None => self.refresh(view),
+        }
+    }
+
+    pub fn select_level(&mut self, view: &mut impl View, index: usize) {
+        let summaries = match &self.pack {
+            Some(pack) => levels::summaries(pack, &self.high_scores, &mut self.difficulties),
+            None => return,
+        };
+        match summaries.get(index) {
+            Some(summary) if !summary.locked => {
+                self.daily = None;
+                self.current_level = index;
+                view.hide_levels();
+                self.restart(view);
+            }
+            _ => warn!("Level {} cannot be played", index),
+        }
+    }
+
+    /// Play today's daily puzzle.
+    pub fn play_daily(&mut self, view: &mut impl View) {
+        let today = (self.today)();
+        if self.daily.as_ref().map(|daily| daily.date) != Some(today) {
+            self.daily = Some(Daily::new(today, daily::level(today)));
+        }
+        view.hide_levels();
+        self.restart(view);
+    }
+
+    pub fn restart(&mut self, view: &mut impl View) {
+        let level = match self.level() {
+            Some(level) => level,
+            None => return,
+        };
+        info!("Creating new game from:\n{}", level.layout);
+        let game = Game::new_from_level(level);
+        self.show_new_game(view, game);
+        self.autosave();
+    }
+
+    pub fn save_game(&self) {
+        if let Some(saved) = self.saved_game() {
+            if let Err(error) = self.saves.save(&saved) {
+                warn!("Cannot save the game: {}", error);
+            }
+        }
+    }
+
+    pub fn load_game(&mut self, view: &mut impl View) {
+        match self.saves.load() {
+            Ok(Some(saved)) => {
+                if self.resume(view, saved) {
+                    self.autosave();
+                }
+            }
+            Ok(None) => info!("No saved game to load"),
+            Err(error) => warn!("Cannot load the saved game: {}", error),
+        }
+    }
+
+    /// Show a saved game, if its level is in the pack or is today's daily puzzle.
+    fn resume(&mut self, view: &mut impl View, saved: SavedGame) -> bool {
+        let index = self.pack.as_ref().and_then(|pack| {
+            pack.levels
+                .iter()
+                .position(|level| LevelKey::of_level(level).to_string() == saved.level)
+        });
+        match index {
+            Some(index) => {
+                self.daily = None;
+                self.current_level = index;
+            }
+            None => {
+                let today = (self.today)();
+                let level = daily::level(today);
+                if LevelKey::of_level(&level).to_string() != saved.level {
+                    warn!("The saved game is for a level which is not in the pack");
+                    return false;
+                }
+                if self.daily.as_ref().map(|daily| daily.date) != Some(today) {
+                    self.daily = Some(Daily::new(today, level));
+                }
+            }
+        }
+        view.hide_levels();
+        self.show_new_game(view, saved.game);
+        true
+    }
+
+    fn show_new_game(&mut self, view: &mut impl View, game: Game) {
+        let difficulty = match (&self.daily, &self.pack) {
+            (Some(daily), _) => daily.difficulty,
+            (None, Some(pack)) => {
+                let level = &pack.levels[self.current_level];
+                self.difficulties.of(level, game.seed().unwrap_or_default())
+            }
+            (None, None) => panic!("Game without levels"),
+        };
+        let level = self.level().expect("Game without levels");
+        let key = LevelKey::of_level(level);
+        let dominoes: Vec<_> = game
+            .dominoes()
+            .iter()
+            .map(|(id, domino)| DominoView::new(*id, domino))
+            .collect();
+        view.reset_dominoes(&dominoes);
+        view.show_high_scores(self.level_high_scores().scores(&key), false);
+        // The times of moves made before the game was saved are unknown.
+        self.hit_times = vec![0; game.moves_made()];
+        self.difficulty = Some(difficulty);
+        self.started = Instant::now();
+        self.playback = None;
+        self.game = Some(game);
+        self.score_saved = false;
+        self.chain = 0;
+        self.hinted_domino = None;
+        self.refresh(view);
+    }
+
+    /// Save the game in progress, or forget it once finished.
+    fn autosave(&self) {
+        if self.playback.is_some() {
+            return;
+        }
+        let saved = match self.saved_game() {
+            Some(saved) if !saved.game.is_finished() => self.saves.autosave(&saved),
+            _ => self.saves.remove_autosave(),
+        };
+        if let Err(error) = saved {
+            warn!("Cannot save the game in progress: {}", error);
+        }
+    }
+
+    fn saved_game(&self) -> Option<SavedGame> {
+        match (self.level(), &self.game) {
+            (Some(level), Some(game)) if self.playback.is_none() => {
+                let key = LevelKey::of_level(level);
+                Some(SavedGame::new(key.to_string(), game.clone()))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn hit(&mut self, view: &mut impl View, id: domino::Id) {
+        let events = match &mut self.game {
+            Some(game) if self.playback.is_none() && game.dominoes().contains_key(&id) => {
+                game.hit_domino_events(id)
+            }
+            _ => return,
+        };
+        self.record_hit_time();
+        self.show_events(view, &events);
+    }
+
+    pub fn undo(&mut self, view: &mut impl View) {
+        if self.playback.is_some() {
+            return;
+        }
+        if let Some(restored) = self.game.as_mut().and_then(Game::undo) {
+            self.hit_times.pop();
+            self.show_restored(view, &restored);
+            self.autosave();
+        }
+    }
+
+    pub fn redo(&mut self, view: &mut impl View) {
+        if self.playback.is_some() {
+            return;
+        }
+        if let Some(events) = self.game.as_mut().and_then(Game::redo_events) {
+            self.record_hit_time();
+            self.show_events(view, &events);
+        }
+    }
+
+    fn record_hit_time(&mut self) {
+        self.hit_times
+            .push(self.started.elapsed().as_millis() as Millis);
+    }
+
+    pub fn show_hint(&mut self, view: &mut impl View) {
+        if self.playback.is_some() {
+            return;
+        }
+        if let Some((id, _)) = self.game.as_ref().and_then(Game::suggest_move) {
+            self.hinted_domino = Some(id);
+            self.refresh(view);
+        }
+    }
+
+    pub fn save_score(&mut self, view: &mut impl View) {
+        let (level, game) = match (self.level(), &self.game) {
+            (Some(level), Some(game))
+                if game.is_finished() && !self.score_saved && self.playback.is_none() =>
+            {
+                (level, game)
+            }
+            _ => return,
+        };
+        let key = LevelKey::of_level(level);
+        let entry =
+            highscores::Entry::new_today(game.score(), game.moves_made(), &view.player_name());
+        let high_scores = self.level_high_scores();
+        if let Err(error) = high_scores.record(key, entry) {
+            warn!("Cannot save high scores: {}", error);
+        }
+        view.show_high_scores(high_scores.scores(&key), true);
+        self.score_saved = true;
+    }
+
+    /// Save the replay of the game being played, to watch it later.
+    pub fn save_replay(&self) {
+        let (level, game) = match (self.level(), &self.game) {
+            (Some(level), Some(game)) if self.playback.is_none() => (level, game),
+            _ => return,
+        };
+        let replay = Replay::of_game(level, game, &self.hit_times);
+        if let Err(error) = self.saves.save_replay(&replay) {
+            warn!("Cannot save the replay: {}", error);
+        }
+    }
+
+    /// Start playing the saved replay.
+    pub fn watch_replay(&mut self, view: &mut impl View) {
+        let replay = match self.saves.load_replay() {
+            Ok(Some(replay)) => replay,
+            Ok(None) => {
+                info!("No replay to watch");
+                return;
+            }
+            Err(error) => {
+                warn!("Cannot load the replay: {}", error);
+                return;
+            }
+        };
+        let level = Level::parse(&replay.level).ok();
+        let level_name = level
+            .as_ref()
+            .map(|level| level.info.name.clone())
+            .unwrap_or_default();
+        let difficulty = level
+            .as_ref()
+            .map(|level| self.difficulties.of(level, replay.seed.unwrap_or_default()));
+        let (player, game) = match Player::new(replay) {
+            Ok(started) => started,
+            Err(error) => {
+                warn!("Cannot play the replay: {}", error);
+                return;
+            }
+        };
+        let dominoes: Vec<_> = game
+            .dominoes()
+            .iter()
+            .map(|(id, domino)| DominoView::new(*id, domino))
+            .collect();
+        view.hide_levels();
+        view.reset_dominoes(&dominoes);
+        self.playback = Some(Playback {
+            player,
+            level_name,
+            paused: false,
+            speed: NORMAL_SPEED,
+        });
+        self.game = Some(game);
+        self.chain = 0;
+        self.hinted_domino = None;
+        self.difficulty = difficulty;
+        self.refresh(view);
+    }
+
+    /// Leave the replay, going back to the game left unfinished or to the levels.
+    pub fn stop_replay(&mut self, view: &mut impl View) {
+        if self.playback.take().is_some() {
+            self.game = None;
+            view.reset_dominoes(&[]);
+            self.refresh(view);
+            self.start(view);
+        }
+    }
+
+    /// Let the time pass in the replay being played. Frontends call this regularly.
+    pub fn tick(&mut self, view: &mut impl View, elapsed: Duration) {
+        let (playback, game) = match (&mut self.playback, &mut self.game) {
+            (Some(playback), Some(game)) if !playback.paused => (playback, game),
+            _ => return,
+        };
+        let speed = REPLAY_SPEEDS[playback.speed] as u128;
+        let elapsed = (elapsed.as_millis() * speed / 100) as Millis;
+        for events in playback.player.advance(game, elapsed) {
+            self.show_events(view, &events);
+        }
+    }
+
+    pub fn toggle_replay_pause(&mut self, view: &mut impl View) {
+        if let Some(playback) = &mut self.playback {
+            playback.paused = !playback.paused;
+            self.refresh(view);
+        }
+    }
+
+    pub fn replay_step_forward(&mut self, view: &mut impl View) {
+        let events = match (&mut self.playback, &mut self.game) {
+            (Some(playback), Some(game)) => {
+                playback.paused = true;
+                playback.player.step_forward(game)
+            }
+            _ => return,
+        };
+        match events {
+            Some(events) => self.show_events(view, &events),
+            None => self.refresh(view),
+        }
+    }
+
+    pub fn replay_step_back(&mut self, view: &mut impl View) {
+        let restored = match (&mut self.playback, &mut self.game) {
+            (Some(playback), Some(game)) => {
+                playback.paused = true;
+                playback.player.step_back(game)
+            }
+            _ => return,
+        };
+        match restored {
+            Some(restored) => self.show_restored(view, &restored),
+            None => self.refresh(view),
+        }
+    }
+
+    pub fn replay_faster(&mut self, view: &mut impl View) {
+        if let Some(playback) = &mut self.playback {
+            playback.speed = (playback.speed + 1).min(REPLAY_SPEEDS.len() - 1);
+            self.refresh(view);
+        }
+    }
+
+    pub fn replay_slower(&mut self, view: &mut impl View) {
+        if let Some(playback) = &mut self.playback {
+            playback.speed = playback.speed.saturating_sub(1);
+            self.refresh(view);
+        }
+    }
+
+    fn show_restored(&mut self, view: &mut impl View, restored: &[(domino::Id, Domino)]) {
+        let dominoes: Vec<_> = restored
+            .iter()
+            .map(|(id, domino)| DominoView::new(*id, domino))
+            .collect();
+        view.restore_dominoes(&dominoes);
+        self.chain = 0;
+        self.hinted_domino = None;
+        self.refresh(view);
+    }
+
+    fn show_events(&mut self, view: &mut impl View, events: &[Event]) {
+        let broken: Vec<_> = events
+            .iter()
+            .filter_map(|event| match event.kind {
+                EventKind::Hit { id } | EventKind::Destroyed { id, .. } => Some((id, event.time)),
+                _ => None,
+            })
+            .collect();
+        view.break_dominoes(&broken);
+        self.chain = events::chain_length(events);
+        self.hinted_domino = None;
+        self.finish_daily();
+        self.refresh(view);
+        self.autosave();
+    }
+
+    /// Write the line to share once the daily puzzle is finished.
+    fn finish_daily(&mut self) {
+        match (&mut self.daily, &self.game) {
+            (Some(daily), Some(game)) if game.is_finished() && self.playback.is_none() => {
+                let summary = daily::summary(daily.date, game, daily.optimal_score);
+                info!("{}", summary);
+                daily.summary = Some(summary);
+            }
+            _ => {}
+        }
+    }
+
+    /// Show the view model if it changed since it was last shown.
+    pub fn refresh(&mut self, view: &mut impl View) {
+        let view_model = self.view_model();
+        if self.shown.as_ref() != Some(&view_model) {
+            view.show_game(&view_model);
+            self.shown = Some(view_model);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_dir::TestDir;
+    use std::collections::BTreeMap;
+
+    #[derive(Default)]
+    struct TestView {
+        game: GameView,
+        dominoes: BTreeMap<domino::Id, DominoView>,
+        broken: Vec<(domino::Id, Time)>,
+        games_shown: usize,
+        high_scores_shown: usize,
+        levels: Option<Vec<LevelSummary>>,
+    }
+
+    impl View for TestView {
+        fn show_game(&mut self, game: &GameView) {
+            self.game = game.clone();
+            self.games_shown += 1;
+        }
+
+        fn reset_dominoes(&mut self, dominoes: &[DominoView]) {
+            self.dominoes = dominoes.iter().map(|domino| (domino.id, *domino)).collect();
+        }
+
+        fn break_dominoes(&mut self, broken: &[(domino::Id, Time)]) {
+            for (id, _) in broken {
+                self.dominoes.remove(id);
+            }
+            self.broken = broken.to_vec();
+        }
+
+        fn restore_dominoes(&mut self, dominoes: &[DominoView]) {
+            self.dominoes
+                .extend(dominoes.iter().map(|domino| (domino.id, *domino)));
+        }
+
+        fn show_high_scores(&mut self, _entries: &[highscores::Entry], _saved: bool) {
+            self.high_scores_shown += 1;
+        }
+
+        fn show_levels(&mut self, levels: &[LevelSummary]) {
+            self.levels = Some(levels.to_vec());
+        }
+
+        fn hide_levels(&mut self) {
+            self.levels = None;
+        }
+
+        fn player_name(&self) -> String {
+            "tester".to_owned()
+        }
+    }
+
+    /// A presenter keeping its high scores and saves in the `directory` of the test.
+    fn presenter_in(directory: &TestDir, pack: &str) -> Presenter {
+        Presenter::new(
+            LevelPack::parse("test", pack),
+            HighScores::load_from(&directory.join("highscores.tsv")).unwrap(),
+            HighScores::load_from(&directory.join("daily.tsv")).unwrap(),
+            Saves::new(Some(directory.to_path_buf())),
+        )
+    }
+
+    #[test]
+    fn playing_a_level() {
+        let directory = TestDir::new();
+        let mut presenter = presenter_in(
+            &directory,
+            "name: First\n[layout]\n--|\n  |\n[values]\n101\n  2\n===\nname: Second\n[layout]\n--",
+        );
+        let mut view = TestView::default();
+        presenter.show_levels(&mut view);
+        let levels = view.levels.as_ref().unwrap();
+        assert_eq!(
+            levels.iter().map(|level| level.locked).collect::<Vec<_>>(),
+            [false, true]
+        );
+
+        presenter.select_level(&mut view, 1);
+        assert!(view.levels.is_some());
+        presenter.select_level(&mut view, 0);
+        assert!(view.levels.is_none());
+        assert_eq!(view.game.level_name, "First");
+        assert_eq!(view.dominoes.len(), 2);
+        assert_eq!(view.high_scores_shown, 1);
+
+        let first = *view.dominoes.keys().next().unwrap();
+        presenter.hit(&mut view, first);
+        assert_eq!(view.broken, [(first, 0), (first + 1, 1)]);
+        assert!(view.dominoes.is_empty());
+        assert!(view.game.finished);
+        assert_eq!(view.game.chain, 1);
+        assert_eq!(view.game.score, 4);
+
+        presenter.undo(&mut view);
+        assert_eq!(view.dominoes.len(), 2);
+        assert_eq!(
+            view.game,
+            GameView {
+                level_name: "First".to_owned(),
+                board_width: 3,
+                board_height: 2,
+                difficulty: Some(Difficulty::Easy),
+                can_redo: true,
+                ..GameView::default()
+            }
+        );
+
+        let shown = view.games_shown;
+        presenter.refresh(&mut view);
+        presenter.hit(&mut view, domino::Id::MAX);
+        assert_eq!(view.games_shown, shown);
+    }
+
+    #[test]
+    fn showing_errors() {
+        let directory = TestDir::new();
+        let mut presenter = presenter_in(&directory, "[layout]\n-");
+        let mut view = TestView::default();
+        presenter.show_levels(&mut view);
+        assert!(view.levels.is_none());
+        assert!(view.game.error.is_some());
+        presenter.restart(&mut view);
+        assert_eq!(view.high_scores_shown, 0);
+    }
+
+    #[test]
+    fn saving_and_resuming() {
+        let directory = TestDir::new();
+        let pack =
+            "name: First\n[layout]\n--\n===\nname: Second\n[layout]\n--|\n  |\n[values]\n101\n  2";
+        let mut presenter = presenter_in(&directory, pack);
+        let mut view = TestView::default();
+        presenter.start(&mut view);
+        assert!(view.levels.is_some());
+
+        presenter.select_level(&mut view, 0);
+        presenter.current_level = 1;
+        presenter.restart(&mut view);
+        let first = *view.dominoes.keys().next().unwrap();
+        presenter.save_game();
+        presenter.hit(&mut view, first);
+        assert!(view.game.finished);
+
+        let mut presenter = presenter_in(&directory, pack);
+        let mut view = TestView::default();
+        presenter.start(&mut view);
+        assert!(view.levels.is_some());
+        presenter.load_game(&mut view);
+        assert!(view.levels.is_none());
+        assert_eq!(view.game.level_name, "Second");
+        assert_eq!(view.dominoes.len(), 2);
+        presenter.hit(&mut view, first + 1);
+        let score = view.game.score;
+
+        let mut presenter = presenter_in(&directory, pack);
+        let mut view = TestView::default();
+        presenter.start(&mut view);
+        assert!(view.levels.is_none());
+        assert_eq!(view.game.level_name, "Second");
+        assert_eq!(view.game.score, score);
+        assert!(!view.game.finished);
+        assert_eq!(view.dominoes.keys().collect::<Vec<_>>(), [&first]);
+        assert!(view.game.can_undo);
+    }
+
+    #[test]
+    fn playing_the_daily_puzzle() {
+        let directory = TestDir::new();
+        let on_date = || chrono::NaiveDate::from_ymd_opt(2021, 12, 31).unwrap();
+        let mut presenter = presenter_in(&directory, "[layout]\n--");
+        presenter.today = on_date;
+        let mut view = TestView::default();
+        presenter.start(&mut view);
+        presenter.play_daily(&mut view);
+        assert!(view.levels.is_none());
+        assert_eq!(view.game.level_name, "Daily 2021-12-31");
+        let first = *view.dominoes.keys().next().unwrap();
+        presenter.hit(&mut view, first);
+
+        let mut presenter = presenter_in(&directory, "[layout]\n--");
+        presenter.today = on_date;
+        let mut view = TestView::default();
+        presenter.start(&mut view);
+        assert_eq!(view.game.level_name, "Daily 2021-12-31");
+        assert!(!view.dominoes.contains_key(&first));
+        while let Some(id) = view.dominoes.keys().next().copied() {
+            assert!(view.game.daily_summary.is_none());
+            presenter.hit(&mut view, id);
+        }
+        let summary = view.game.daily_summary.clone().unwrap();
+        assert!(summary.starts_with("Domino Breaker daily 2021-12-31"));
+        presenter.save_score(&mut view);
+        let key = LevelKey::of_level(&daily::level(on_date()));
+        assert_eq!(presenter.daily_scores.scores(&key).len(), 1);
+        assert!(presenter.high_scores.scores(&key).is_empty());
+        presenter.undo(&mut view);
+        assert!(view.game.daily_summary.is_none());
+
+        presenter.select_level(&mut view, 0);
+        assert_eq!(view.game.daily_summary, None);
+        assert_eq!(view.game.level_name, "");
+    }
+
+    #[test]
+    fn watching_replays() {
+        let directory = TestDir::new();
+        let mut presenter = presenter_in(
+            &directory,
+            "name: First\n[layout]\n--|--\n  |--\n[values]\n10123\n  245",
+        );
+        let mut view = TestView::default();
+        presenter.select_level(&mut view, 0);
+        let ids: Vec<_> = view.dominoes.keys().copied().collect();
+        presenter.hit(&mut view, ids[1]);
+        presenter.hit(&mut view, ids[3]);
+        let score = view.game.score;
+        let left = view.dominoes.clone();
+        presenter.save_replay();
+
+        presenter.watch_replay(&mut view);
+        assert_eq!(view.dominoes.len(), ids.len());
+        let replay = view.game.replay.unwrap();
+        assert_eq!((replay.step, replay.steps, replay.paused), (0, 2, false));
+        presenter.hit(&mut view, ids[0]);
+        presenter.undo(&mut view);
+        assert_eq!(view.dominoes.len(), ids.len());
+
+        presenter.tick(&mut view, Duration::from_secs(60));
+        assert_eq!(view.dominoes, left);
+        assert_eq!(view.game.score, score);
+        presenter.replay_step_back(&mut view);
+        let replay = view.game.replay.unwrap();
+        assert_eq!((replay.step, replay.paused), (1, true));
+        presenter.replay_faster(&mut view);
+        presenter.replay_faster(&mut view);
+        presenter.replay_faster(&mut view);
+        assert_eq!(view.game.replay.unwrap().speed, 400);
+        presenter.replay_step_forward(&mut view);
+        assert_eq!(view.dominoes, left);
+
+        presenter.stop_replay(&mut view);
+        assert!(view.game.replay.is_none());
+        assert_eq!(view.game.level_name, "First");
+        assert_eq!(view.dominoes, left);
+    }
+}
@@ -1,8 +1,13 @@
-use crate::game;
-use crate::game::{board, domino};
-use crate::log::info;
+use crate::highscores::{self, HighScores};
+use crate::levels::{self, LevelSummary};
+use crate::presenter::{DominoView, GameView, Presenter, View};
+use crate::saves::Saves;
+use dombreaker_core::game;
+use dombreaker_core::game::events::Time;
+use dombreaker_core::game::{board, domino};
 use qmetaobject::*;
-use std::collections::HashSet;
+use std::collections::HashMap;
+use std::time::Duration;
 
 #[derive(QObject, Default)]
 pub struct Domino {
@@ -12,21 +17,21 @@ pub struct Domino {
     head_value: qt_property!(domino::Value; CONST),
     tail_value: qt_property!(domino::Value; CONST),
     horizontal: qt_property!(bool; CONST),
-    broken: qt_signal!(),
+    broken: qt_signal!(time: i32),
 }
 
 impl Domino {
-    fn new_boxed(id: domino::Id, domino: game::domino::Domino) -> DominoBox {
+    fn new_boxed(domino: &DominoView) -> DominoBox {
         let created = Self {
             base: Default::default(),
-            game_id: id,
+            game_id: domino.id,
             board_position: QPointF {
-                x: domino.position.x as f64,
-                y: domino.position.y as f64,
+                x: domino.x as f64,
+                y: domino.y as f64,
             },
-            head_value: domino.values.head,
-            tail_value: domino.values.tail,
-            horizontal: domino.orientation == domino::Orientation::Horizontal,
+            head_value: domino.head_value,
+            tail_value: domino.tail_value,
+            horizontal: domino.horizontal,
             broken: Default::default(),
         };
         let boxed = QObjectBox::new(created);
@@ -48,26 +53,113 @@ impl SimpleListItem for DominoBox {
     }
 }
 
+#[derive(SimpleListItem, Default)]
+pub struct HighScore {
+    pub player: String,
+    pub score: i32,
+    pub date: String,
+    pub moves: usize,
+}
+
+impl From<&highscores::Entry> for HighScore {
+    fn from(entry: &highscores::Entry) -> Self {
+        Self {
+            player: entry.player.clone(),
+            score: entry.score,
+            date: entry.date.to_string(),
+            moves: entry.moves,
+        }
+    }
+}
+
+#[derive(SimpleListItem, Default)]
+pub struct LevelItem {
+    pub name: String,
+    pub difficulty: String,
+    pub has_best_score: bool,
+    pub best_score: i32,
+    pub locked: bool,
+}
+
+impl From<&LevelSummary> for LevelItem {
+    fn from(summary: &LevelSummary) -> Self {
+        Self {
+            name: summary.name.clone(),
+            difficulty: summary.difficulty.to_string(),
+            has_best_score: summary.best_score.is_some(),
+            best_score: summary.best_score.unwrap_or_default(),
+            locked: summary.locked,
+        }
+    }
+}
+
 #[derive(QObject, Default)]
 pub struct Game {
     base: qt_base_class!(trait QObject),
-
     board_width: qt_property!(board::Coord; NOTIFY board_changed),
     board_height: qt_property!(board::Coord; NOTIFY board_changed),
+    level_name: qt_property!(QString; NOTIFY board_changed),
+    seed: qt_property!(QString; NOTIFY board_changed),
+    difficulty: qt_property!(QString; NOTIFY board_changed),
     dominoes: qt_property!(QPointer<SimpleListModel<DominoBox>>; READ dominoes NOTIFY dominoes_changed),
-    score: qt_property!(game::Score; READ score NOTIFY score_changed),
-    finished: qt_property!(bool; READ is_finished NOTIFY finished_changed),
+    score: qt_property!(game::Score; NOTIFY score_changed),
+    chain: qt_property!(i32; NOTIFY chain_changed),
+    finished: qt_property!(bool; NOTIFY finished_changed),
+    can_undo: qt_property!(bool; NOTIFY history_changed),
+    can_redo: qt_property!(bool; NOTIFY history_changed),
+    error: qt_property!(QString; NOTIFY error_changed),
+    hinted_domino: qt_property!(i32; NOTIFY hint_changed),
+    player_name: qt_property!(QString; NOTIFY player_name_changed),
+    high_scores: qt_property!(QPointer<SimpleListModel<HighScore>>; READ high_scores NOTIFY high_scores_changed),
+    score_saved: qt_property!(bool; NOTIFY high_scores_changed),
+    levels: qt_property!(QPointer<SimpleListModel<LevelItem>>; READ levels NOTIFY levels_changed),
+    selecting_level: qt_property!(bool; NOTIFY levels_changed),
+    replaying: qt_property!(bool; NOTIFY replay_changed),
+    replay_paused: qt_property!(bool; NOTIFY replay_changed),
+    replay_step: qt_property!(i32; NOTIFY replay_changed),
+    replay_steps: qt_property!(i32; NOTIFY replay_changed),
+    replay_speed: qt_property!(i32; NOTIFY replay_changed),
+    daily_summary: qt_property!(QString; NOTIFY finished_changed),
 
-    new_game: qt_method!(fn(&self, board_description: String)),
+    start: qt_method!(fn(&self)),
+    show_levels: qt_method!(fn(&self)),
+    select_level: qt_method!(fn(&self, index: usize)),
+    play_daily: qt_method!(fn(&self)),
+    restart: qt_method!(fn(&self)),
     domino_hit: qt_method!(fn(&self, id: domino::Id)),
+    undo: qt_method!(fn(&self)),
+    redo: qt_method!(fn(&self)),
+    show_hint: qt_method!(fn(&self)),
+    save_score: qt_method!(fn(&self)),
+    save_game: qt_method!(fn(&self)),
+    load_game: qt_method!(fn(&self)),
+    save_replay: qt_method!(fn(&self)),
+    watch_replay: qt_method!(fn(&self)),
+    toggle_replay_pause: qt_method!(fn(&self)),
+    replay_step_forward: qt_method!(fn(&self)),
+    replay_step_back: qt_method!(fn(&self)),
+    replay_faster: qt_method!(fn(&self)),
+    replay_slower: qt_method!(fn(&self)),
+    stop_replay: qt_method!(fn(&self)),
+    tick: qt_method!(fn(&self, elapsed_ms: i32)),
 
     board_changed: qt_signal!(),
     dominoes_changed: qt_signal!(),
     score_changed: qt_signal!(),
+    chain_changed: qt_signal!(),
     finished_changed: qt_signal!(),
+    history_changed: qt_signal!(),
+    error_changed: qt_signal!(),
+    hint_changed: qt_signal!(),
+    player_name_changed: qt_signal!(),
+    high_scores_changed: qt_signal!(),
+    levels_changed: qt_signal!(),
+    replay_changed: qt_signal!(),
 
-    game: Option<game::Game>,
+    presenter: Option<Presenter>,
     m_dominoes: QObjectBox<SimpleListModel<DominoBox>>,
+    m_high_scores: QObjectBox<SimpleListModel<HighScore>>,
+    m_levels: QObjectBox<SimpleListModel<LevelItem>>,
 }
 
 impl Game {
@@ -75,14 +167,154 @@ impl Game {
         self.m_dominoes.pinned().borrow().into()
     }
 
-    fn score(&self) -> game::Score {
-        self.game
-            .as_ref()
-            .map(game::Game::score)
+    fn high_scores(&mut self) -> QPointer<SimpleListModel<HighScore>> {
+        self.m_high_scores.pinned().borrow().into()
+    }
+
+    fn levels(&mut self) -> QPointer<SimpleListModel<LevelItem>> {
+        self.m_levels.pinned().borrow().into()
+    }
+
+    /// Call the presenter, created on first use, with this object as its view.
+    fn with_presenter(&mut self, action: impl FnOnce(&mut Presenter, &mut Self)) {
+        let mut presenter = self.presenter.take().unwrap_or_else(|| {
+            Presenter::new(
+                levels::load_pack(),
+                HighScores::load(),
+                HighScores::load_daily(),
+                Saves::in_data_dir(),
+            )
+        });
+        action(&mut presenter, self);
+        self.presenter = Some(presenter);
+    }
+
+    fn start(&mut self) {
+        self.with_presenter(Presenter::start)
+    }
+
+    fn show_levels(&mut self) {
+        self.with_presenter(Presenter::show_levels)
+    }
+
+    fn select_level(&mut self, index: usize) {
+        self.with_presenter(|presenter, view| presenter.select_level(view, index))
+    }
+
+    fn play_daily(&mut self) {
+        self.with_presenter(Presenter::play_daily)
+    }
+
+    fn restart(&mut self) {
+        self.with_presenter(Presenter::restart)
+    }
+
+    fn domino_hit(&mut self, id: domino::Id) {
+        self.with_presenter(|presenter, view| presenter.hit(view, id))
+    }
+
+    fn undo(&mut self) {
+        self.with_presenter(Presenter::undo)
+    }
+
+    fn redo(&mut self) {
+        self.with_presenter(Presenter::redo)
+    }
+
+    fn show_hint(&mut self) {
+        self.with_presenter(Presenter::show_hint)
+    }
+
+    fn save_score(&mut self) {
+        self.with_presenter(Presenter::save_score)
+    }
+
+    fn save_game(&mut self) {
+        self.with_presenter(|presenter, _| presenter.save_game())
+    }
+
+    fn load_game(&mut self) {
+        self.with_presenter(Presenter::load_game)
+    }
+
+    fn save_replay(&mut self) {
+        self.with_presenter(|presenter, _| presenter.save_replay())
+    }
+
+    fn watch_replay(&mut self) {
+        self.with_presenter(Presenter::watch_replay)
+    }
+
+    fn toggle_replay_pause(&mut self) {
+        self.with_presenter(Presenter::toggle_replay_pause)
+    }
+
+    fn replay_step_forward(&mut self) {
+        self.with_presenter(Presenter::replay_step_forward)
+    }
+
+    fn replay_step_back(&mut self) {
+        self.with_presenter(Presenter::replay_step_back)
+    }
+
+    fn replay_faster(&mut self) {
+        self.with_presenter(Presenter::replay_faster)
+    }
+
+    fn replay_slower(&mut self) {
+        self.with_presenter(Presenter::replay_slower)
+    }
+
+    fn stop_replay(&mut self) {
+        self.with_presenter(Presenter::stop_replay)
+    }
+
+    fn tick(&mut self, elapsed_ms: i32) {
+        let elapsed = Duration::from_millis(elapsed_ms.max(0) as u64);
+        self.with_presenter(|presenter, view| presenter.tick(view, elapsed))
+    }
+}
+
+impl View for Game {
+    fn show_game(&mut self, game: &GameView) {
+        self.level_name = game.level_name.as_str().into();
+        self.board_width = game.board_width;
+        self.board_height = game.board_height;
+        self.seed = game
+            .seed
+            .map(|seed| seed.to_string())
+            .unwrap_or_default()
+            .into();
+        self.difficulty = game
+            .difficulty
+            .map(|difficulty| difficulty.to_string())
             .unwrap_or_default()
+            .into();
+        self.board_changed();
+        self.score = game.score;
+        self.score_changed();
+        self.chain = game.chain as i32;
+        self.chain_changed();
+        self.finished = game.finished;
+        self.daily_summary = game.daily_summary.clone().unwrap_or_default().into();
+        self.finished_changed();
+        self.can_undo = game.can_undo;
+        self.can_redo = game.can_redo;
+        self.history_changed();
+        self.error = game.error.clone().unwrap_or_default().into();
+        self.error_changed();
+        self.hinted_domino = game.hinted_domino.map_or(-1, |id| id as i32);
+        self.hint_changed();
+        let replay = game.replay.unwrap_or_default();
+        self.replaying = game.replay.is_some();
+        self.replay_paused = replay.paused;
+        self.replay_step = replay.step as i32;
+        self.replay_steps = replay.steps as i32;
+        self.replay_speed = replay.speed as i32;
+        self.replay_changed();
     }
 
-    fn new_game(&mut self, board_description: String) {
+    fn reset_dominoes(&mut self, dominoes: &[DominoView]) {
         // Remove old dominoes and notify about the fact.
         //
         // This is needed to avoid situation (bug?) where cpp object of recreated
@@ -90,49 +322,22 @@ impl Game {
         // refreshed (it assumes same address == same object).
         self.m_dominoes = Default::default();
         self.dominoes_changed();
-        info!("Creating new game from:\n{}", board_description);
-        let game = game::Game::new_generated(&board_description);
-        let dominoes: SimpleListModel<DominoBox> = game
-            .dominoes()
-            .iter()
-            .map(|(id, domino)| Domino::new_boxed(*id, *domino))
-            .collect();
-
-        self.board_width = game.board().width();
-        self.board_height = game.board().height();
-        self.board_changed();
-
+        let dominoes: SimpleListModel<DominoBox> = dominoes.iter().map(Domino::new_boxed).collect();
         self.m_dominoes = QObjectBox::new(dominoes);
         self.m_dominoes.pinned().get_or_create_cpp_object();
         self.dominoes_changed();
-
-        self.game = Some(game);
-        self.score_changed();
-        self.finished_changed();
     }
 
-    fn domino_hit(&mut self, id: domino::Id) {
-        if let Some(game) = &mut self.game {
-            let result = game.hit_domino(id);
-            let is_finished = game.is_finished();
-            self.remove_dominoes(result);
-            self.score_changed();
-            if is_finished {
-                self.finished_changed();
-            }
-        }
-    }
-
-    fn remove_dominoes(&mut self, hit_result: Vec<game::DominoRemoved>) {
-        let removed_set: HashSet<domino::Id> = hit_result.iter().map(|d| d.id).collect();
+    fn break_dominoes(&mut self, broken: &[(domino::Id, Time)]) {
+        let removal_times: HashMap<domino::Id, Time> = broken.iter().copied().collect();
         let mut checked_index = 0;
         let dominoes = self.m_dominoes.pinned();
         let mut dominoes = dominoes.borrow_mut();
         while checked_index < dominoes.row_count() as usize {
             let domino = dominoes[checked_index].0.pinned();
             let domino = domino.borrow();
-            if removed_set.contains(&domino.game_id) {
-                domino.broken();
+            if let Some(time) = removal_times.get(&domino.game_id) {
+                domino.broken(*time as i32);
                 dominoes.remove(checked_index)
             } else {
                 checked_index += 1
@@ -140,7 +345,38 @@ impl Game {
         }
     }
 
-    fn is_finished(&self) -> bool {
-        self.game.as_ref().map_or(true, |game| game.is_finished())
+    fn restore_dominoes(&mut self, restored: &[DominoView]) {
+        let dominoes = self.m_dominoes.pinned();
+        let mut dominoes = dominoes.borrow_mut();
+        for domino in restored {
+            dominoes.push(Domino::new_boxed(domino));
+        }
+    }
+
+    fn show_high_scores(&mut self, entries: &[highscores::Entry], saved: bool) {
+        if self.player_name.to_string().is_empty() {
+            self.player_name = highscores::default_player_name().into();
+            self.player_name_changed();
+        }
+        let entries = entries.iter().map(HighScore::from).collect();
+        self.m_high_scores.pinned().borrow_mut().reset_data(entries);
+        self.score_saved = saved;
+        self.high_scores_changed();
+    }
+
+    fn show_levels(&mut self, levels: &[LevelSummary]) {
+        let items = levels.iter().map(LevelItem::from).collect();
+        self.m_levels.pinned().borrow_mut().reset_data(items);
+        self.selecting_level = true;
+        self.levels_changed();
+    }
+
+    fn hide_levels(&mut self) {
+        self.selecting_level = false;
+        self.levels_changed();
+    }
+
+    fn player_name(&self) -> String {
+        self.player_name.to_string()
     }
 }
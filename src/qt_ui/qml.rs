@@ -8,6 +8,8 @@ qrc! {qt_ui_qmls,
         "qml/DominoBackground.qml" as "DominoBackground.qml",
         "qml/DominoHalf.qml" as "DominoHalf.qml",
         "qml/Dot.qml" as "Dot.qml",
+        "qml/HighScores.qml" as "HighScores.qml",
+        "qml/LevelSelect.qml" as "LevelSelect.qml",
     }
 }
 
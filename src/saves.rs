@@ -0,0 +1,97 @@
+//! Games kept between runs in the user's data directory: the one saved by the player, as JSON,
+//! the game in progress, saved after every move to resume it on the next start, and the last
+//! replay saved.
+
+use crate::log::warn;
+use dombreaker_core::game::replay::{Replay, ReplayError};
+use dombreaker_core::game::save::{SaveError, SaveFormat, SavedGame};
+use std::path::PathBuf;
+
+const SAVE_FILE: &str = "save.json";
+const AUTOSAVE_FILE: &str = "autosave.bin";
+const REPLAY_FILE: &str = "last.replay";
+
+pub struct Saves {
+    directory: Option<PathBuf>,
+}
+
+impl Saves {
+    /// Saves in the given directory, or nowhere.
+    pub fn new(directory: Option<PathBuf>) -> Self {
+        Self { directory }
+    }
+
+    pub fn in_data_dir() -> Self {
+        let directory = dirs::data_dir().map(|directory| directory.join("dombreaker"));
+        if directory.is_none() {
+            warn!("No data directory found, games will not be saved");
+        }
+        Self::new(directory)
+    }
+
+    pub fn save(&self, saved: &SavedGame) -> Result<(), SaveError> {
+        self.write(SAVE_FILE, saved, SaveFormat::Json)
+    }
+
+    /// The game saved by the player, if there is one.
+    pub fn load(&self) -> Result<Option<SavedGame>, SaveError> {
+        self.read(SAVE_FILE)
+    }
+
+    pub fn autosave(&self, saved: &SavedGame) -> Result<(), SaveError> {
+        self.write(AUTOSAVE_FILE, saved, SaveFormat::Binary)
+    }
+
+    pub fn load_autosave(&self) -> Result<Option<SavedGame>, SaveError> {
+        self.read(AUTOSAVE_FILE)
+    }
+
+    pub fn remove_autosave(&self) -> Result<(), SaveError> {
+        let path = match &self.directory {
+            Some(directory) => directory.join(AUTOSAVE_FILE),
+            None => return Ok(()),
+        };
+        match std::fs::remove_file(path) {
+            Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(SaveError::Io(error)),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn save_replay(&self, replay: &Replay) -> Result<(), ReplayError> {
+        match &self.directory {
+            Some(directory) => replay.save(&directory.join(REPLAY_FILE)),
+            None => Ok(()),
+        }
+    }
+
+    pub fn load_replay(&self) -> Result<Option<Replay>, ReplayError> {
+        let path = match &self.directory {
+            Some(directory) => directory.join(REPLAY_FILE),
+            None => return Ok(None),
+        };
+        match Replay::load(&path) {
+            Ok(replay) => Ok(Some(replay)),
+            Err(ReplayError::Io(error)) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    fn write(&self, file: &str, saved: &SavedGame, format: SaveFormat) -> Result<(), SaveError> {
+        match &self.directory {
+            Some(directory) => saved.save(&directory.join(file), format),
+            None => Ok(()),
+        }
+    }
+
+    fn read(&self, file: &str) -> Result<Option<SavedGame>, SaveError> {
+        let path = match &self.directory {
+            Some(directory) => directory.join(file),
+            None => return Ok(None),
+        };
+        match SavedGame::load(&path) {
+            Ok(saved) => Ok(Some(saved)),
+            Err(SaveError::Io(error)) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+}
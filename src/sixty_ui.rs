@@ -1,46 +1,56 @@
 mod model;
 
-use crate::game::{domino, Game};
-use std::cell::RefCell;
+use crate::highscores::HighScores;
+use crate::levels;
+use crate::presenter::Presenter;
+use crate::saves::Saves;
+use dombreaker_core::game::domino;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 
 sixtyfps::include_modules!();
 
-const LEVEL: &str = "--------\n\
-                    |------|\n\
-                    ||----||\n\
-                    |||--|||\n\
-                    |||--|||\n\
-                    ||----||\n\
-                    |------|";
-
 struct Application {
-    game: Game,
+    presenter: Presenter,
     handler: model::Handler,
 }
 
 impl Application {
     fn new(main_window: &Main) -> Rc<RefCell<Self>> {
-        let game = Game::new_generated(LEVEL);
-        let handler = model::Handler::initialize(&main_window, &game);
-        let this = Self { game, handler };
-        Rc::new(RefCell::new(this))
+        let mut handler = model::Handler::new(main_window);
+        let mut presenter = Presenter::new(
+            levels::load_pack(),
+            HighScores::load(),
+            HighScores::load_daily(),
+            Saves::in_data_dir(),
+        );
+        presenter.start(&mut handler);
+        Rc::new(RefCell::new(Self { presenter, handler }))
     }
 
-    fn on_restart_handler(this: Rc<RefCell<Self>>) -> impl Fn() {
+    /// Handle a callback by calling the presenter with the handler as its view.
+    fn on(
+        this: &Rc<RefCell<Self>>,
+        action: impl Fn(&mut Presenter, &mut model::Handler),
+    ) -> impl Fn() {
+        let this = this.clone();
         move || {
             let mut borrow = this.borrow_mut();
-            let self_ref = &mut *borrow;
-            self_ref.game = Game::new_generated(LEVEL);
-            self_ref.handler.reinitialize(&self_ref.game);
+            let Self { presenter, handler } = &mut *borrow;
+            action(presenter, handler)
         }
     }
 
-    fn on_domino_clicked(this: Rc<RefCell<Self>>) -> impl Fn(i32) {
-        move |id| {
-            let mut self_ref = this.borrow_mut();
-            let result = self_ref.game.hit_domino(id as domino::Id);
-            self_ref.handler.update(&self_ref.game, &result);
+    fn on_index(
+        this: &Rc<RefCell<Self>>,
+        action: impl Fn(&mut Presenter, &mut model::Handler, i32),
+    ) -> impl Fn(i32) {
+        let this = this.clone();
+        move |index| {
+            let mut borrow = this.borrow_mut();
+            let Self { presenter, handler } = &mut *borrow;
+            action(presenter, handler, index)
         }
     }
 }
@@ -70,11 +80,55 @@ pub fn main() {
     let main_window = Main::new();
     let application = Application::new(&main_window);
     let model = main_window.global::<GameModel>();
-    model.on_restart(Application::on_restart_handler(application.clone()));
-    model.on_domino_clicked(Application::on_domino_clicked(application));
+    model.on_restart(Application::on(&application, Presenter::restart));
+    model.on_domino_clicked(Application::on_index(
+        &application,
+        |presenter, view, id| presenter.hit(view, id as domino::Id),
+    ));
+    model.on_undo(Application::on(&application, Presenter::undo));
+    model.on_redo(Application::on(&application, Presenter::redo));
+    model.on_show_hint(Application::on(&application, Presenter::show_hint));
+    model.on_save_score(Application::on(&application, Presenter::save_score));
+    model.on_save_game(Application::on(&application, |presenter, _| {
+        presenter.save_game()
+    }));
+    model.on_load_game(Application::on(&application, Presenter::load_game));
+    model.on_save_replay(Application::on(&application, |presenter, _| {
+        presenter.save_replay()
+    }));
+    model.on_watch_replay(Application::on(&application, Presenter::watch_replay));
+    model.on_toggle_replay_pause(Application::on(
+        &application,
+        Presenter::toggle_replay_pause,
+    ));
+    model.on_replay_step_forward(Application::on(
+        &application,
+        Presenter::replay_step_forward,
+    ));
+    model.on_replay_step_back(Application::on(&application, Presenter::replay_step_back));
+    model.on_replay_faster(Application::on(&application, Presenter::replay_faster));
+    model.on_replay_slower(Application::on(&application, Presenter::replay_slower));
+    model.on_stop_replay(Application::on(&application, Presenter::stop_replay));
+    model.on_show_levels(Application::on(&application, Presenter::show_levels));
+    model.on_select_level(Application::on_index(
+        &application,
+        |presenter, view, index| presenter.select_level(view, index as usize),
+    ));
+    model.on_play_daily(Application::on(&application, Presenter::play_daily));
 
     let info = main_window.global::<DominoInfo>();
     info.on_is_dot_visible(is_dot_visible);
 
+    let last_tick = Cell::new(Instant::now());
+    let tick = Application::on(&application, move |presenter, view| {
+        presenter.tick(view, last_tick.replace(Instant::now()).elapsed())
+    });
+    let replay_timer = sixtyfps::Timer::default();
+    replay_timer.start(
+        sixtyfps::TimerMode::Repeated,
+        Duration::from_millis(50),
+        tick,
+    );
+
     main_window.run();
 }
@@ -1,21 +1,24 @@
-use crate::game::domino::{Domino, Id, Orientation};
-use crate::game::{DominoRemoved, Game};
-use crate::sixty_ui::{DominoModel, GameModel, Main, Position};
+use crate::highscores;
+use crate::levels::LevelSummary;
+use crate::presenter::{DominoView, GameView, View};
+use crate::sixty_ui::{DominoModel, GameModel, HighScoreModel, LevelModel, Main, Position};
+use dombreaker_core::game::domino::Id;
+use dombreaker_core::game::events::Time;
 use sixtyfps::{ComponentHandle, Model, ModelHandle, VecModel};
-use std::collections::HashSet;
+use std::collections::HashMap;
 use std::rc::Rc;
 
-impl DominoModel {
-    fn from_domino(id: Id, domino: &Domino) -> Self {
+impl From<&DominoView> for DominoModel {
+    fn from(domino: &DominoView) -> Self {
         Self {
-            game_id: id as i32,
+            game_id: domino.id as i32,
             board_position: Position {
-                x: domino.position.x as i32,
-                y: domino.position.y as i32,
+                x: domino.x as i32,
+                y: domino.y as i32,
             },
-            head_value: domino.values.head as i32,
-            tail_value: domino.values.tail as i32,
-            horizontal: domino.orientation == Orientation::Horizontal,
+            head_value: domino.head_value as i32,
+            tail_value: domino.tail_value as i32,
+            horizontal: domino.horizontal,
             disappearing: false,
         }
     }
@@ -28,44 +31,47 @@ pub struct Dominoes {
 }
 
 impl Dominoes {
-    pub fn new(game: &Game) -> Self {
-        let dominoes: Vec<DominoModel> = game
-            .dominoes()
-            .iter()
-            .map(|(id, domino)| DominoModel::from_domino(*id, domino))
-            .collect();
+    pub fn new(dominoes: Vec<DominoModel>) -> Self {
         Self {
             dominoes: Rc::new(VecModel::from(dominoes)),
             broken: Rc::new(VecModel::from(vec![])),
         }
     }
 
-    pub fn new_in_game_model(game: &Game, model: &GameModel) -> Self {
-        let this = Self::new(game);
+    pub fn new_in_game_model(dominoes: Vec<DominoModel>, model: &GameModel) -> Self {
+        let this = Self::new(dominoes);
         model.set_dominoes(ModelHandle::new(this.dominoes.clone()));
         model.set_broken_dominoes(ModelHandle::new(this.broken.clone()));
         this
     }
 
-    fn update(&self, removed: &[DominoRemoved]) {
-        let removed_ids: HashSet<Id> = removed.iter().map(|d| d.id).collect();
+    fn update(&self, broken: &[(Id, Time)]) {
+        let removal_times: HashMap<Id, Time> = broken.iter().copied().collect();
         let mut i = 0;
         while i < self.dominoes.row_count() {
             let domino = self.dominoes.row_data(i);
-            if removed_ids.contains(&(domino.game_id as Id)) {
+            if let Some(time) = removal_times.get(&(domino.game_id as Id)) {
                 self.dominoes.remove(i);
-                self.add_broken_domino(domino);
+                self.add_broken_domino(domino, *time);
             } else {
                 i += 1;
             }
         }
     }
 
-    fn add_broken_domino(&self, domino: DominoModel) {
+    fn restore(&self, restored: &[DominoView]) {
+        for domino in restored {
+            self.dominoes.push(domino.into());
+        }
+    }
+
+    /// Show the domino fading out, later for dominoes removed later in the chain reaction.
+    fn add_broken_domino(&self, domino: DominoModel, time: Time) {
         let broken = self.broken.clone();
         let broken_index = broken.row_count();
         broken.push(domino);
-        sixtyfps::Timer::single_shot(std::time::Duration::from_millis(300), move || {
+        let delay = 300 + 200 * time as u64;
+        sixtyfps::Timer::single_shot(std::time::Duration::from_millis(delay), move || {
             let mut domino = broken.row_data(broken_index);
             domino.disappearing = true;
             broken.set_row_data(broken_index, domino);
@@ -80,33 +86,105 @@ pub struct Handler {
 }
 
 impl Handler {
-    pub fn initialize(main: &Main, game: &Game) -> Self {
+    pub fn new(main: &Main) -> Self {
         let game_model = main.global::<GameModel>();
-        game_model.set_board_width(game.board().width() as i32);
-        game_model.set_board_height(game.board().height() as i32);
-        game_model.set_score(game.score() as i32);
-        game_model.set_finished(game.is_finished());
-        let dominoes = Dominoes::new_in_game_model(game, &game_model);
+        game_model.set_player_name(highscores::default_player_name().into());
         Self {
             main: main.as_weak(),
-            dominoes,
+            dominoes: Dominoes::new_in_game_model(Vec::new(), &game_model),
         }
     }
+}
+
+impl View for Handler {
+    fn show_game(&mut self, game: &GameView) {
+        let main = self.main.upgrade().unwrap();
+        let game_model = main.global::<GameModel>();
+        game_model.set_error(game.error.clone().unwrap_or_default().into());
+        game_model.set_level_name(game.level_name.as_str().into());
+        game_model.set_board_width(game.board_width as i32);
+        game_model.set_board_height(game.board_height as i32);
+        game_model.set_seed(
+            game.seed
+                .map(|seed| seed.to_string())
+                .unwrap_or_default()
+                .into(),
+        );
+        game_model.set_difficulty(
+            game.difficulty
+                .map(|difficulty| difficulty.to_string())
+                .unwrap_or_default()
+                .into(),
+        );
+        game_model.set_score(game.score);
+        game_model.set_chain(game.chain as i32);
+        game_model.set_finished(game.finished);
+        game_model.set_can_undo(game.can_undo);
+        game_model.set_can_redo(game.can_redo);
+        game_model.set_hinted_domino(game.hinted_domino.map_or(-1, |id| id as i32));
+        let replay = game.replay.unwrap_or_default();
+        game_model.set_replaying(game.replay.is_some());
+        game_model.set_replay_paused(replay.paused);
+        game_model.set_replay_step(replay.step as i32);
+        game_model.set_replay_steps(replay.steps as i32);
+        game_model.set_replay_speed(replay.speed as i32);
+        game_model.set_daily_summary(game.daily_summary.clone().unwrap_or_default().into());
+    }
 
-    pub fn reinitialize(&mut self, game: &Game) {
+    fn reset_dominoes(&mut self, dominoes: &[DominoView]) {
         let main = self.main.upgrade().unwrap();
-        *self = Handler::initialize(&main, game);
+        let dominoes = dominoes.iter().map(DominoModel::from).collect();
+        self.dominoes = Dominoes::new_in_game_model(dominoes, &main.global::<GameModel>());
+    }
+
+    fn break_dominoes(&mut self, broken: &[(Id, Time)]) {
+        self.dominoes.update(broken);
+    }
+
+    fn restore_dominoes(&mut self, dominoes: &[DominoView]) {
+        self.dominoes.restore(dominoes);
     }
 
-    pub fn update(&self, game: &Game, removed_dominoes: &[DominoRemoved]) {
+    fn show_high_scores(&mut self, entries: &[highscores::Entry], saved: bool) {
         let main = self.main.upgrade().unwrap();
         let game_model = main.global::<GameModel>();
-        let score_gained = removed_dominoes
+        let entries: Vec<HighScoreModel> = entries
             .iter()
-            .map(|d| d.score_awarded as i32)
-            .sum::<i32>();
-        game_model.set_score(game_model.get_score() + score_gained);
-        game_model.set_finished(game.is_finished());
-        self.dominoes.update(removed_dominoes);
+            .map(|entry| HighScoreModel {
+                player: entry.player.as_str().into(),
+                score: entry.score,
+                date: entry.date.to_string().into(),
+                moves: entry.moves as i32,
+            })
+            .collect();
+        game_model.set_high_scores(ModelHandle::new(Rc::new(VecModel::from(entries))));
+        game_model.set_score_saved(saved);
+    }
+
+    fn show_levels(&mut self, levels: &[LevelSummary]) {
+        let main = self.main.upgrade().unwrap();
+        let game_model = main.global::<GameModel>();
+        let levels: Vec<LevelModel> = levels
+            .iter()
+            .map(|level| LevelModel {
+                name: level.name.as_str().into(),
+                difficulty: level.difficulty.to_string().into(),
+                has_best_score: level.best_score.is_some(),
+                best_score: level.best_score.unwrap_or_default(),
+                locked: level.locked,
+            })
+            .collect();
+        game_model.set_levels(ModelHandle::new(Rc::new(VecModel::from(levels))));
+        game_model.set_selecting_level(true);
+    }
+
+    fn hide_levels(&mut self) {
+        let main = self.main.upgrade().unwrap();
+        main.global::<GameModel>().set_selecting_level(false);
+    }
+
+    fn player_name(&self) -> String {
+        let main = self.main.upgrade().unwrap();
+        main.global::<GameModel>().get_player_name().into()
     }
 }
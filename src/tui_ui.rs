@@ -0,0 +1,105 @@
+mod screen;
+
+use crate::highscores::HighScores;
+use crate::levels;
+use crate::log::{self, error, LevelFilter};
+use crate::presenter::Presenter;
+use crate::saves::Saves;
+use crossterm::event::{self, Event};
+use crossterm::{cursor, execute, terminal};
+use screen::{Action, Screen};
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+/// How long to wait for input before redrawing explosions.
+const FRAME: Duration = Duration::from_millis(50);
+
+pub fn main() {
+    // Log lines would be printed over the board.
+    log::set_max_level(LevelFilter::Off);
+    let mut presenter = Presenter::new(
+        levels::load_pack(),
+        HighScores::load(),
+        HighScores::load_daily(),
+        Saves::in_data_dir(),
+    );
+    let mut screen = Screen::default();
+    let mut stdout = std::io::stdout();
+    let result = setup_terminal(&mut stdout).and_then(|()| {
+        presenter.start(&mut screen);
+        run(&mut presenter, &mut screen, &mut stdout)
+    });
+    let restored = restore_terminal(&mut stdout);
+    log::set_max_level(LevelFilter::Trace);
+    if let Err(error) = result.and(restored) {
+        error!("Terminal error: {}", error);
+    }
+}
+
+fn setup_terminal(stdout: &mut impl Write) -> crossterm::Result<()> {
+    terminal::enable_raw_mode()?;
+    execute!(
+        stdout,
+        terminal::EnterAlternateScreen,
+        event::EnableMouseCapture,
+        cursor::Hide
+    )
+}
+
+fn restore_terminal(stdout: &mut impl Write) -> crossterm::Result<()> {
+    execute!(
+        stdout,
+        cursor::Show,
+        event::DisableMouseCapture,
+        terminal::LeaveAlternateScreen
+    )?;
+    terminal::disable_raw_mode()
+}
+
+fn run(
+    presenter: &mut Presenter,
+    screen: &mut Screen,
+    stdout: &mut impl Write,
+) -> crossterm::Result<()> {
+    let mut redraw = true;
+    let mut last_tick = Instant::now();
+    loop {
+        if redraw || screen.is_animating() {
+            screen.draw(stdout)?;
+        }
+        redraw = event::poll(FRAME)?;
+        presenter.tick(screen, last_tick.elapsed());
+        last_tick = Instant::now();
+        if !redraw {
+            continue;
+        }
+        let action = match event::read()? {
+            Event::Key(key) => screen.action_for_key(key),
+            Event::Mouse(mouse) => screen.action_for_mouse(mouse),
+            Event::Resize(_, _) => None,
+        };
+        match action {
+            Some(Action::Quit) => return Ok(()),
+            Some(Action::Hit(id)) => presenter.hit(screen, id),
+            Some(Action::Restart) => presenter.restart(screen),
+            Some(Action::Undo) => presenter.undo(screen),
+            Some(Action::Redo) => presenter.redo(screen),
+            Some(Action::Hint) => presenter.show_hint(screen),
+            Some(Action::SaveScore) => presenter.save_score(screen),
+            Some(Action::SaveGame) => presenter.save_game(),
+            Some(Action::LoadGame) => presenter.load_game(screen),
+            Some(Action::SaveReplay) => presenter.save_replay(),
+            Some(Action::WatchReplay) => presenter.watch_replay(screen),
+            Some(Action::TogglePause) => presenter.toggle_replay_pause(screen),
+            Some(Action::StepForward) => presenter.replay_step_forward(screen),
+            Some(Action::StepBack) => presenter.replay_step_back(screen),
+            Some(Action::Faster) => presenter.replay_faster(screen),
+            Some(Action::Slower) => presenter.replay_slower(screen),
+            Some(Action::StopReplay) => presenter.stop_replay(screen),
+            Some(Action::ShowLevels) => presenter.show_levels(screen),
+            Some(Action::SelectLevel(index)) => presenter.select_level(screen, index),
+            Some(Action::PlayDaily) => presenter.play_daily(screen),
+            None => {}
+        }
+    }
+}
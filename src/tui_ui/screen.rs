@@ -0,0 +1,363 @@
+use crate::highscores;
+use crate::levels::LevelSummary;
+use crate::presenter::{DominoView, GameView, View};
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use crossterm::style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor};
+use crossterm::{cursor, queue, terminal};
+use dombreaker_core::game::board::Coord;
+use dombreaker_core::game::domino::{Id, Value};
+use dombreaker_core::game::events::Time;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+/// Terminal columns taken by a tile.
+const TILE_WIDTH: u16 = 3;
+/// Terminal row of the top of the board, below the score line.
+const BOARD_TOP: u16 = 2;
+/// Delay between the steps of a chain reaction.
+const STEP: Duration = Duration::from_millis(200);
+const FLASH: Duration = Duration::from_millis(300);
+const DOMINO_COLORS: [Color; 3] = [Color::White, Color::Grey, Color::DarkCyan];
+
+pub enum Action {
+    Quit,
+    Hit(Id),
+    Restart,
+    Undo,
+    Redo,
+    Hint,
+    SaveScore,
+    SaveGame,
+    LoadGame,
+    SaveReplay,
+    WatchReplay,
+    TogglePause,
+    StepForward,
+    StepBack,
+    Faster,
+    Slower,
+    StopReplay,
+    ShowLevels,
+    SelectLevel(usize),
+    PlayDaily,
+}
+
+/// A tile of a broken domino, flashing for a moment once the explosion reaches it.
+struct Flash {
+    tile: (Coord, Coord),
+    start: Instant,
+}
+
+#[derive(Default)]
+pub struct Screen {
+    game: GameView,
+    dominoes: BTreeMap<Id, DominoView>,
+    flashes: Vec<Flash>,
+    cursor: (Coord, Coord),
+    high_scores: Vec<highscores::Entry>,
+    score_saved: bool,
+    levels: Option<Vec<LevelSummary>>,
+    selected_level: usize,
+}
+
+fn tiles_of(domino: &DominoView) -> [((Coord, Coord), Value); 2] {
+    let tail = if domino.horizontal {
+        (domino.x + 1, domino.y)
+    } else {
+        (domino.x, domino.y + 1)
+    };
+    [
+        ((domino.x, domino.y), domino.head_value),
+        (tail, domino.tail_value),
+    ]
+}
+
+impl Screen {
+    fn domino_at(&self, tile: (Coord, Coord)) -> Option<Id> {
+        self.dominoes
+            .values()
+            .find(|domino| {
+                tiles_of(domino)
+                    .iter()
+                    .any(|(position, _)| *position == tile)
+            })
+            .map(|domino| domino.id)
+    }
+
+    pub fn action_for_key(&mut self, key: KeyEvent) -> Option<Action> {
+        if let Some(levels) = &self.levels {
+            return match key.code {
+                KeyCode::Up => {
+                    self.selected_level = self.selected_level.saturating_sub(1);
+                    None
+                }
+                KeyCode::Down => {
+                    self.selected_level = (self.selected_level + 1).min(levels.len() - 1);
+                    None
+                }
+                KeyCode::Enter => Some(Action::SelectLevel(self.selected_level)),
+                KeyCode::Char('d') => Some(Action::PlayDaily),
+                KeyCode::Char('q') | KeyCode::Esc => Some(Action::Quit),
+                _ => None,
+            };
+        }
+        if self.game.replay.is_some() {
+            return match key.code {
+                KeyCode::Char(' ') | KeyCode::Char('p') => Some(Action::TogglePause),
+                KeyCode::Right => Some(Action::StepForward),
+                KeyCode::Left => Some(Action::StepBack),
+                KeyCode::Char('+') => Some(Action::Faster),
+                KeyCode::Char('-') => Some(Action::Slower),
+                KeyCode::Char('x') | KeyCode::Esc => Some(Action::StopReplay),
+                KeyCode::Char('q') => Some(Action::Quit),
+                _ => None,
+            };
+        }
+        let (x, y) = self.cursor;
+        match key.code {
+            KeyCode::Left => self.cursor.0 = x.saturating_sub(1),
+            KeyCode::Right => self.cursor.0 = (x + 1).min(self.game.board_width.max(1) - 1),
+            KeyCode::Up => self.cursor.1 = y.saturating_sub(1),
+            KeyCode::Down => self.cursor.1 = (y + 1).min(self.game.board_height.max(1) - 1),
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                return self.domino_at(self.cursor).map(Action::Hit)
+            }
+            KeyCode::Char('r') => return Some(Action::Restart),
+            KeyCode::Char('u') => return Some(Action::Undo),
+            KeyCode::Char('y') => return Some(Action::Redo),
+            KeyCode::Char('h') => return Some(Action::Hint),
+            KeyCode::Char('s') => return Some(Action::SaveScore),
+            KeyCode::Char('l') => return Some(Action::ShowLevels),
+            KeyCode::Char('d') => return Some(Action::PlayDaily),
+            KeyCode::Char('w') => return Some(Action::SaveGame),
+            KeyCode::Char('o') => return Some(Action::LoadGame),
+            KeyCode::Char('c') => return Some(Action::SaveReplay),
+            KeyCode::Char('v') => return Some(Action::WatchReplay),
+            KeyCode::Char('q') | KeyCode::Esc => return Some(Action::Quit),
+            _ => {}
+        }
+        None
+    }
+
+    pub fn action_for_mouse(&mut self, mouse: MouseEvent) -> Option<Action> {
+        if self.levels.is_some()
+            || self.game.replay.is_some()
+            || mouse.kind != MouseEventKind::Down(MouseButton::Left)
+        {
+            return None;
+        }
+        let row = mouse.row.checked_sub(BOARD_TOP)?;
+        let tile = ((mouse.column / TILE_WIDTH) as Coord, row as Coord);
+        self.cursor = tile;
+        self.domino_at(tile).map(Action::Hit)
+    }
+
+    pub fn is_animating(&self) -> bool {
+        !self.flashes.is_empty() || self.game.replay.is_some_and(|replay| !replay.paused)
+    }
+
+    pub fn draw(&mut self, out: &mut impl Write) -> crossterm::Result<()> {
+        queue!(
+            out,
+            terminal::Clear(terminal::ClearType::All),
+            cursor::MoveTo(0, 0)
+        )?;
+        if let Some(error) = &self.game.error {
+            queue!(
+                out,
+                SetForegroundColor(Color::Red),
+                Print("Invalid level: ")
+            )?;
+            queue!(out, Print(error.replace('\n', "\r\n")), ResetColor)?;
+        } else if let Some(levels) = &self.levels {
+            self.draw_levels(out, levels)?;
+        } else {
+            self.draw_status(out)?;
+            self.draw_board(out)?;
+            self.draw_footer(out)?;
+        }
+        out.flush()
+    }
+
+    fn draw_levels(&self, out: &mut impl Write, levels: &[LevelSummary]) -> crossterm::Result<()> {
+        queue!(
+            out,
+            Print("Select a level (Enter to play, d: daily puzzle, q to quit)")
+        )?;
+        for (index, level) in levels.iter().enumerate() {
+            let best = level.best_score.map(|score| format!("best {}", score));
+            let line = format!(
+                "{} {:<24} {:<8} {}",
+                if level.locked { "#" } else { " " },
+                level.name,
+                level.difficulty.to_string(),
+                best.unwrap_or_default()
+            );
+            queue!(out, cursor::MoveTo(0, index as u16 + BOARD_TOP))?;
+            if index == self.selected_level {
+                queue!(out, SetBackgroundColor(Color::DarkBlue))?;
+            }
+            queue!(out, Print(line), ResetColor)?;
+        }
+        Ok(())
+    }
+
+    fn draw_status(&self, out: &mut impl Write) -> crossterm::Result<()> {
+        let game = &self.game;
+        queue!(
+            out,
+            Print(format!("{}  Score: {}", game.level_name, game.score))
+        )?;
+        if let Some(difficulty) = game.difficulty {
+            queue!(out, Print(format!("  ({})", difficulty)))?;
+        }
+        if let Some(seed) = game.seed {
+            queue!(out, Print(format!("  Seed: {}", seed)))?;
+        }
+        if let Some(replay) = game.replay {
+            let state = if replay.paused { "paused" } else { "playing" };
+            let line = format!(
+                "  Replay {}/{} {} x{}",
+                replay.step,
+                replay.steps,
+                state,
+                replay.speed as f32 / 100.0
+            );
+            queue!(out, Print(line))?;
+        }
+        if game.chain >= 2 {
+            let chain = format!("  Chain x{}!", game.chain);
+            queue!(
+                out,
+                SetForegroundColor(Color::Red),
+                Print(chain),
+                ResetColor
+            )?;
+        }
+        Ok(())
+    }
+
+    fn draw_board(&mut self, out: &mut impl Write) -> crossterm::Result<()> {
+        for domino in self.dominoes.values() {
+            let color = if Some(domino.id) == self.game.hinted_domino {
+                Color::Yellow
+            } else {
+                DOMINO_COLORS[domino.id as usize % DOMINO_COLORS.len()]
+            };
+            for (tile, value) in tiles_of(domino) {
+                let (background, foreground) = if tile == self.cursor {
+                    (Color::Black, color)
+                } else {
+                    (color, Color::Black)
+                };
+                queue!(
+                    out,
+                    Self::move_to_tile(tile),
+                    SetBackgroundColor(background),
+                    SetForegroundColor(foreground),
+                    Print(format!("{:^3}", value)),
+                    ResetColor
+                )?;
+            }
+        }
+        let now = Instant::now();
+        self.flashes.retain(|flash| now < flash.start + FLASH);
+        for flash in self.flashes.iter().filter(|flash| now >= flash.start) {
+            queue!(
+                out,
+                Self::move_to_tile(flash.tile),
+                SetBackgroundColor(Color::Red),
+                SetForegroundColor(Color::Yellow),
+                Print(" * "),
+                ResetColor
+            )?;
+        }
+        if self.domino_at(self.cursor).is_none() {
+            queue!(out, Self::move_to_tile(self.cursor), Print("[ ]"))?;
+        }
+        Ok(())
+    }
+
+    fn draw_footer(&self, out: &mut impl Write) -> crossterm::Result<()> {
+        let mut row = BOARD_TOP + self.game.board_height as u16 + 1;
+        let help = if self.game.replay.is_some() {
+            "space: pause  left/right: step  +/-: speed  x: stop replay  q: quit"
+        } else {
+            "arrows/mouse: select  enter: hit  u: undo  y: redo  h: hint  r: restart  \
+            w: save  o: load  c: save replay  v: watch replay  l: levels  d: daily  q: quit"
+        };
+        queue!(out, cursor::MoveTo(0, row), Print(help))?;
+        if !self.game.finished || self.game.replay.is_some() {
+            return Ok(());
+        }
+        row += 2;
+        if let Some(summary) = &self.game.daily_summary {
+            queue!(out, cursor::MoveTo(0, row), Print(summary))?;
+            row += 2;
+        }
+        queue!(out, cursor::MoveTo(0, row), Print("Finished! High scores:"))?;
+        for entry in &self.high_scores {
+            row += 1;
+            let line = format!(
+                "{:>6}  {:<16} {} ({} moves)",
+                entry.score, entry.player, entry.date, entry.moves
+            );
+            queue!(out, cursor::MoveTo(0, row), Print(line))?;
+        }
+        if !self.score_saved {
+            let prompt = format!("s: save score as {}", highscores::default_player_name());
+            queue!(out, cursor::MoveTo(0, row + 2), Print(prompt))?;
+        }
+        Ok(())
+    }
+
+    fn move_to_tile((x, y): (Coord, Coord)) -> cursor::MoveTo {
+        cursor::MoveTo(x as u16 * TILE_WIDTH, y as u16 + BOARD_TOP)
+    }
+}
+
+impl View for Screen {
+    fn show_game(&mut self, game: &GameView) {
+        self.game = game.clone();
+    }
+
+    fn reset_dominoes(&mut self, dominoes: &[DominoView]) {
+        self.dominoes = dominoes.iter().map(|domino| (domino.id, *domino)).collect();
+        self.flashes.clear();
+        self.cursor = (0, 0);
+    }
+
+    fn break_dominoes(&mut self, broken: &[(Id, Time)]) {
+        let now = Instant::now();
+        for (id, time) in broken {
+            if let Some(domino) = self.dominoes.remove(id) {
+                let start = now + STEP * *time as u32;
+                let flashes = tiles_of(&domino).map(|(tile, _)| Flash { tile, start });
+                self.flashes.extend(flashes);
+            }
+        }
+    }
+
+    fn restore_dominoes(&mut self, dominoes: &[DominoView]) {
+        self.dominoes
+            .extend(dominoes.iter().map(|domino| (domino.id, *domino)));
+    }
+
+    fn show_high_scores(&mut self, entries: &[highscores::Entry], saved: bool) {
+        self.high_scores = entries.to_vec();
+        self.score_saved = saved;
+    }
+
+    fn show_levels(&mut self, levels: &[LevelSummary]) {
+        self.levels = Some(levels.to_vec());
+    }
+
+    fn hide_levels(&mut self) {
+        self.levels = None;
+    }
+
+    fn player_name(&self) -> String {
+        highscores::default_player_name()
+    }
+}